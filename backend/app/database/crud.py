@@ -362,6 +362,14 @@ def has_pending_backup_task(db: Session, src_path: str) -> bool:
     )
 
 
+def count_pending_backup_tasks(db: Session) -> int:
+    return (
+        db.query(models.BackupTask)
+        .filter(models.BackupTask.status.in_(["pending", "processing"]))
+        .count()
+    )
+
+
 def get_next_backup_task(db: Session):
     return (
         db.query(models.BackupTask)
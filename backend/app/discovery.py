@@ -0,0 +1,71 @@
+import json
+import logging
+import os
+import time
+from typing import Any
+
+logger = logging.getLogger("locus")
+
+DISCOVERY_FILE_NAME = "backend.json"
+
+
+# Lets the desktop shell attach to a backend already serving the same data dir
+# (e.g. the systemd user service) instead of spawning a second one.
+def discovery_file_path() -> str | None:
+    data_dir = os.getenv("LOCUS_DATA_DIR", "").strip()
+    if not data_dir:
+        return None
+    # A socket-mode backend belongs to the shell that spawned it; nothing may attach.
+    if os.getenv("LOCUS_UDS", "").strip():
+        return None
+    return os.path.join(os.path.abspath(data_dir), DISCOVERY_FILE_NAME)
+
+
+def write_discovery_file(host: str, port: int, version: str) -> str | None:
+    path = discovery_file_path()
+    if path is None:
+        return None
+
+    record: dict[str, Any] = {
+        "pid": os.getpid(),
+        "host": host,
+        "port": port,
+        "data_dir": os.path.dirname(path),
+        "version": version,
+        "started_at": int(time.time()),
+    }
+
+    temp_path = f"{path}.{os.getpid()}.tmp"
+    try:
+        os.makedirs(os.path.dirname(path), exist_ok=True)
+        with open(temp_path, "w", encoding="utf-8") as handle:
+            json.dump(record, handle)
+        os.replace(temp_path, path)
+    except OSError as exc:
+        logger.warning("[Discovery] Failed to write %s: %s", path, exc)
+        try:
+            os.remove(temp_path)
+        except OSError:
+            pass
+        return None
+
+    return path
+
+
+def remove_discovery_file() -> None:
+    path = discovery_file_path()
+    if path is None:
+        return
+
+    # Another backend may have replaced the file since; only remove our own record.
+    try:
+        with open(path, "r", encoding="utf-8") as handle:
+            record = json.load(handle)
+    except (OSError, ValueError):
+        return
+
+    if isinstance(record, dict) and record.get("pid") == os.getpid():
+        try:
+            os.remove(path)
+        except OSError as exc:
+            logger.warning("[Discovery] Failed to remove %s: %s", path, exc)
@@ -8,6 +8,7 @@ from app.database import models, crud
 from app.monitor import monitor_service, register_restore_start, process_backup
 from app import storage
 from app import event_stream
+from app import discovery
 from app.snapshot_service import snapshot_service
 from sqlalchemy import create_engine, event, text
 from sqlalchemy.orm import sessionmaker, Session
@@ -19,9 +20,11 @@ import uvicorn
 import os
 import errno
 import gzip
+import hmac
 import asyncio
 import socket
 import shutil  # Added shutil for file operations
+import signal
 import json
 import re
 import uuid
@@ -64,12 +67,39 @@ def _set_sqlite_pragmas(dbapi_connection, connection_record):
 
 SessionLocal = sessionmaker(autocommit=False, autoflush=False, bind=engine)
 
+# Bump whenever _run_startup_migrations gains a step; reported to the desktop shell.
+SCHEMA_VERSION = 1
+
+
+class SchemaVersionError(RuntimeError):
+    pass
+
+
+def _read_schema_version(conn) -> int:
+    return int(conn.execute(text("PRAGMA user_version")).scalar() or 0)
+
 
 # Initialize database tables (creates tables if missing).
 def init_db():
+    with engine.connect() as conn:
+        stored_version = _read_schema_version(conn)
+    # A newer Locus may have reshaped tables this build would then write to blindly.
+    if stored_version > SCHEMA_VERSION:
+        raise SchemaVersionError(
+            f"Database schema {stored_version} is newer than this backend "
+            f"supports ({SCHEMA_VERSION}); update Locus to open this data directory"
+        )
+
     models.Base.metadata.create_all(bind=engine)
-    _run_startup_migrations()
+    if stored_version < SCHEMA_VERSION:
+        logger.info("[DB] Migrating schema %s -> %s", stored_version, SCHEMA_VERSION)
+        _run_startup_migrations()
     with engine.begin() as conn:
+        conn.execute(text(f"PRAGMA user_version={SCHEMA_VERSION}"))
+        if _read_schema_version(conn) != SCHEMA_VERSION:
+            raise SchemaVersionError(
+                f"Database schema version could not be recorded as {SCHEMA_VERSION}"
+            )
         conn.execute(text("PRAGMA journal_mode=WAL"))
         conn.execute(text("PRAGMA synchronous=NORMAL"))
         conn.execute(text("PRAGMA busy_timeout=30000"))
@@ -232,6 +262,8 @@ DIAGNOSTICS_MAX_FILE_SIZE_BYTES = 5 * 1024 * 1024
 DIAGNOSTICS_LOG_FILE_NAME = "stability-events.jsonl"
 DIAGNOSTICS_ARCHIVE_FILE_NAME = "stability-events.prev.jsonl"
 APP_VERSION = "1.5.0"
+# Bump on breaking changes to the HTTP contract; the desktop shell refuses a mismatch.
+API_VERSION = 1
 
 _diagnostics_log_lock = threading.Lock()
 
@@ -284,6 +316,40 @@ def _pick_api_port(host: str, preferred_port: int) -> int:
     )
 
 
+SD_LISTEN_FDS_START = 3
+
+
+# systemd-style socket handoff from the desktop shell, which keeps the port bound from
+# the moment it picks it so nothing else can grab it before we start serving.
+def _take_inherited_listener(fd: int = SD_LISTEN_FDS_START) -> socket.socket | None:
+    raw_count = os.environ.pop("LISTEN_FDS", "").strip()
+    listen_pid = os.environ.pop("LISTEN_PID", "").strip()
+    os.environ.pop("LISTEN_FDNAMES", None)
+    if not raw_count:
+        return None
+    if listen_pid and listen_pid != str(os.getpid()):
+        return None
+    try:
+        if int(raw_count) < 1:
+            return None
+        sock = socket.socket(fileno=fd)
+    except (ValueError, OSError) as exc:
+        logger.warning(f"[Startup] Ignoring inherited listener: {exc}")
+        return None
+    if sock.type != socket.SOCK_STREAM:
+        sock.detach()
+        return None
+    return sock
+
+
+def _read_configured_port() -> int:
+    raw_port = os.getenv("LOCUS_PORT", str(DEFAULT_API_PORT)).strip()
+    try:
+        return int(raw_port)
+    except ValueError:
+        return DEFAULT_API_PORT
+
+
 def _is_process_alive(pid: int) -> bool:
     if pid <= 0:
         return False
@@ -549,6 +615,7 @@ class SnapshotResetPayload(BaseModel):
 
 
 class SnapshotSettingsUpdate(BaseModel):
+    enabled: bool | None = None
     interval_seconds: int | None = Field(default=None, ge=5, le=300)
     retention_days: int | None = Field(default=None, ge=1, le=365)
     exclude_private_browsing: bool | None = None
@@ -1004,6 +1071,14 @@ async def lifespan(app: FastAPI):
 
     snapshot_service.start()
     logger.info("[Startup] snapshot thread started")
+
+    discovery_path = discovery.write_discovery_file(
+        os.getenv("LOCUS_HOST", "127.0.0.1").strip() or "127.0.0.1",
+        _read_configured_port(),
+        APP_VERSION,
+    )
+    if discovery_path:
+        logger.info("[Startup] discovery file written to %s", discovery_path)
     logger.info(
         "[Startup] lifespan startup completed in %.2fs",
         time.perf_counter() - startup_started,
@@ -1012,6 +1087,7 @@ async def lifespan(app: FastAPI):
     yield
 
     # Shutdown
+    discovery.remove_discovery_file()
     stop_event.set()
     snapshot_service.stop()
     monitor_service.stop()
@@ -1058,6 +1134,7 @@ async def add_security_headers(
         "/auth/unlock",
         "/auth/lock",
         "/auth/reset",
+        "/system/shutdown",
     }
 
     if request.url.path not in exempt_paths and os.getenv("LOCUS_SKIP_AUTH") != "true":
@@ -1101,12 +1178,36 @@ async def add_security_headers(
     return response
 
 
+API_TOKEN_HEADER = "X-Locus-Token"
+API_TOKEN_QUERY_PARAM = "locus_token"
+
+
+# Registered after add_security_headers so it wraps it: an untrusted local caller
+# is rejected before the lock check even looks at the database.
+@app.middleware("http")
+async def require_api_token(
+    request: Request, call_next: Callable[[Request], Awaitable[Response]]
+) -> Response:
+    expected = os.getenv("LOCUS_API_TOKEN", "").strip()
+    if not expected or request.method == "OPTIONS":
+        return await call_next(request)
+
+    # EventSource and <img> cannot set headers, so the query param is accepted too.
+    supplied = request.headers.get(API_TOKEN_HEADER) or request.query_params.get(
+        API_TOKEN_QUERY_PARAM, ""
+    )
+    if not hmac.compare_digest(supplied.encode("utf-8"), expected.encode("utf-8")):
+        return Response(status_code=401, content="Invalid API token")
+
+    return await call_next(request)
+
+
 app.add_middleware(
     CORSMiddleware,
     allow_origins=_build_cors_origins(),
     allow_credentials=True,
     allow_methods=["GET", "POST", "PATCH", "DELETE", "OPTIONS"],
-    allow_headers=["Content-Type", "Authorization"],
+    allow_headers=["Content-Type", "Authorization", API_TOKEN_HEADER],
 )
 
 
@@ -1125,16 +1226,148 @@ def read_root():
     return {"status": "running", "service": "LOCUS Backend"}
 
 
+def _health_components(db_ready: bool) -> dict[str, str]:
+    return {
+        "db": "ready" if db_ready else "error",
+        "watcher": "ready" if monitor_service.is_running() else "starting",
+        "snapshot_engine": "ready" if snapshot_service.is_running() else "stopped",
+    }
+
+
+# `schema` is what the database itself records, read back rather than assumed.
+def _version_info(stored_schema: int | None = None) -> dict[str, Any]:
+    schema = SCHEMA_VERSION if stored_schema is None else stored_schema
+    return {"app": APP_VERSION, "api": API_VERSION, "schema": schema}
+
+
 @app.get("/health")
 # Healthcheck endpoint to verify DB connectivity and background services.
 def health_check(db: DbSession):
     # Simple check to ensure DB is reachable
     try:
-        db.execute(text("SELECT 1"))
-        return {"db": "connected", "background_service": "active"}
+        stored_schema = _read_schema_version(db)
+        return {
+            "db": "connected",
+            "background_service": "active",
+            "components": _health_components(db_ready=True),
+            "version": _version_info(stored_schema),
+        }
     except Exception as e:
         logger.error(f"Health check DB error: {e}", exc_info=True)
-        return {"db": "error", "error": str(e)}
+        return {
+            "db": "error",
+            "error": str(e),
+            "components": _health_components(db_ready=False),
+            "version": _version_info(),
+        }
+
+
+# --- Graceful shutdown (desktop shell handshake) ---
+SHUTDOWN_DRAIN_DEFAULT_SECONDS = 8.0
+SHUTDOWN_DRAIN_MAX_SECONDS = 120.0
+_shutdown_started = threading.Event()
+
+
+class ShutdownRequest(BaseModel):
+    drain_timeout_seconds: float = Field(
+        default=SHUTDOWN_DRAIN_DEFAULT_SECONDS, ge=0, le=SHUTDOWN_DRAIN_MAX_SECONDS
+    )
+
+
+# Returns how many backup tasks were still queued when we stopped waiting.
+def _wait_for_backup_queue_drain(timeout_seconds: float) -> int:
+    deadline = time.monotonic() + timeout_seconds
+    while True:
+        with SessionLocal() as db:
+            pending = crud.count_pending_backup_tasks(db)
+        if pending == 0 or time.monotonic() >= deadline:
+            return pending
+        time.sleep(0.2)
+
+
+def _flush_database() -> None:
+    with engine.begin() as conn:
+        conn.execute(text("PRAGMA wal_checkpoint(TRUNCATE)"))
+
+
+def _request_server_exit() -> None:
+    # Under `service_entry.py --reload` this process is only a worker the reloader would
+    # respawn; stopping the reloader makes it shut the worker down the same graceful way.
+    # The reloader is always our direct parent, so any other pid did not come from it.
+    reload_supervisor_pid = _try_parse_positive_pid(
+        os.getenv("LOCUS_RELOAD_SUPERVISOR_PID", "")
+    )
+    if (
+        reload_supervisor_pid is not None
+        and reload_supervisor_pid == os.getppid()
+        and os.name != "nt"
+    ):
+        os.kill(reload_supervisor_pid, signal.SIGINT)
+        return
+
+    # uvicorn treats SIGINT as a graceful stop: in-flight requests (e.g. a checkpoint
+    # restore) finish and the lifespan shutdown still runs.
+    signal.raise_signal(signal.SIGINT)
+
+
+def _graceful_shutdown(drain_timeout_seconds: float) -> None:
+    started = time.monotonic()
+    pending = _wait_for_backup_queue_drain(drain_timeout_seconds)
+    if pending:
+        logger.warning(
+            f"[Shutdown] Drain deadline reached with {pending} backup task(s) still queued"
+        )
+    else:
+        logger.info(
+            f"[Shutdown] Backup queue drained in {time.monotonic() - started:.2f}s"
+        )
+
+    try:
+        _flush_database()
+    except Exception as exc:
+        logger.error(f"[Shutdown] Database flush failed: {exc}", exc_info=True)
+
+    _request_server_exit()
+
+
+@app.post("/system/shutdown", status_code=202)
+# Drain the backup queue, flush SQLite, then stop the server; the shell waits for exit.
+def request_shutdown(payload: ShutdownRequest | None = None):
+    # Exempt from the lock so the shell can always stop its sidecar; the launch token
+    # (checked by require_api_token) keeps every other local caller out.
+    if not os.getenv("LOCUS_API_TOKEN", "").strip():
+        raise HTTPException(
+            status_code=403, detail="Shutdown requires the launch API token"
+        )
+    if not _shutdown_started.is_set():
+        _shutdown_started.set()
+        drain_timeout = (payload or ShutdownRequest()).drain_timeout_seconds
+        logger.info(f"[Shutdown] Requested by shell; draining for up to {drain_timeout}s")
+        threading.Thread(
+            target=_graceful_shutdown, args=(drain_timeout,), daemon=True
+        ).start()
+    return {"status": "draining"}
+
+
+# --- Monitoring endpoints ---
+@app.get("/monitoring")
+# Report whether file monitoring is paused.
+def get_monitoring_state():
+    return {"paused": monitor_service.is_paused()}
+
+
+@app.post("/monitoring/pause")
+# Stop watching every folder until resumed; the pause lasts until the backend restarts.
+def pause_monitoring():
+    monitor_service.pause()
+    return {"paused": True}
+
+
+@app.post("/monitoring/resume")
+# Re-attach watches for all configured folders.
+def resume_monitoring():
+    monitor_service.resume()
+    return {"paused": False}
 
 
 # --- Watched Paths endpoints ---
@@ -2670,12 +2903,15 @@ def get_timeline(
         403: {"description": "Path must be within a watched folder"},
     },
 )
-def list_file_versions(path: str, db: DbSession):
-    """List available versions for a specific file path"""
+def list_file_versions(path: str, db: DbSession, create: bool = True):
+    """List available versions for a specific file path.
+
+    With create=false the lookup is read-only: an unknown file is not hashed or recorded.
+    """
     _assert_path_allowed(path, db)
 
     versions = crud.get_file_versions(db, path)
-    if not versions and os.path.exists(path):
+    if create and not versions and os.path.exists(path):
         # Fallback/Recovery: If file exists but has no history,
         # try to link it using its current content (if valid).
         current_hash = storage.calculate_file_hash(path)
@@ -2982,6 +3218,8 @@ def get_snapshot_settings(db: DbSession):
 )
 def update_snapshot_settings(payload: SnapshotSettingsUpdate, db: DbSession):
     updates: dict[str, object] = {}
+    if payload.enabled is not None:
+        updates["enabled"] = payload.enabled
     if payload.interval_seconds is not None:
         updates["interval_seconds"] = payload.interval_seconds
     if payload.retention_days is not None:
@@ -3393,19 +3631,29 @@ def delete_snapshot(snapshot_id: int, db: DbSession):
 
 
 if __name__ == "__main__":
-    host = os.getenv("LOCUS_HOST", "127.0.0.1").strip() or "127.0.0.1"
-    raw_port = os.getenv("LOCUS_PORT", str(DEFAULT_API_PORT)).strip()
-    try:
-        preferred_port = int(raw_port)
-    except ValueError:
-        preferred_port = DEFAULT_API_PORT
+    # Socket mode (Linux desktop shell): the shell owns the 0700 directory around the
+    # socket and proxies all webview traffic, so no TCP port is opened at all.
+    uds_path = os.getenv("LOCUS_UDS", "").strip()
+    if uds_path:
+        _start_parent_watchdog_if_configured()
+        uvicorn.run(app, uds=uds_path)
+    elif (inherited := _take_inherited_listener()) is not None:
+        # The discovery file must advertise the port we actually serve.
+        os.environ["LOCUS_PORT"] = str(inherited.getsockname()[1])
+        _start_parent_watchdog_if_configured()
+        uvicorn.Server(uvicorn.Config(app)).run(sockets=[inherited])
+    else:
+        host = os.getenv("LOCUS_HOST", "127.0.0.1").strip() or "127.0.0.1"
+        preferred_port = _read_configured_port()
 
-    selected_port = _pick_api_port(host, preferred_port)
-    if selected_port != preferred_port:
-        print(
-            f"[Startup] Preferred port {preferred_port} unavailable, using {selected_port} instead"
-        )
+        selected_port = _pick_api_port(host, preferred_port)
+        if selected_port != preferred_port:
+            print(
+                f"[Startup] Preferred port {preferred_port} unavailable, using {selected_port} instead"
+            )
+        # The discovery file must advertise the port we actually bind.
+        os.environ["LOCUS_PORT"] = str(selected_port)
 
-    _start_parent_watchdog_if_configured()
+        _start_parent_watchdog_if_configured()
 
-    uvicorn.run(app, host=host, port=selected_port)
+        uvicorn.run(app, host=host, port=selected_port)
@@ -358,6 +358,7 @@ class FileMonitorService:
         self._queue_thread: threading.Thread | None = None
         self._event_thread: threading.Thread | None = None
         self._running = False
+        self._paused = False
         self._state_lock = threading.RLock()
 
     def _enqueue_command(self, cmd: str, payload: object | None = None):
@@ -423,6 +424,17 @@ class FileMonitorService:
                 )
                 self._event_thread.start()
 
+    def is_running(self) -> bool:
+        """True once the observer thread owns a started watchdog Observer."""
+        with self._state_lock:
+            thread = self._monitor_thread
+        return bool(
+            self._running
+            and thread is not None
+            and thread.is_alive()
+            and self.observer is not None
+        )
+
     def enqueue_fs_event(self, event_data: dict[str, str | None]) -> None:
         if not self._running:
             self.start()
@@ -655,6 +667,13 @@ class FileMonitorService:
 
     def _do_sync_watches(self):
         """Internal sync logic; must run on monitor thread."""
+        if self.is_paused():
+            with self._state_lock:
+                watched_paths = set(self.active_watches) | set(self.root_watches)
+            for path in watched_paths:
+                self._stop_watching_path(path)
+            return
+
         db = SessionLocal()
         try:
             paths = crud.get_watched_paths(db)
@@ -690,6 +709,23 @@ class FileMonitorService:
         """Request a sync on the monitor thread."""
         self._enqueue_command("sync", None)
 
+    def is_paused(self) -> bool:
+        with self._state_lock:
+            return self._paused
+
+    # Pausing drops every watch; changes made while paused are not backed up.
+    def pause(self):
+        with self._state_lock:
+            self._paused = True
+        print("[MonitorService] Monitoring paused")
+        self.sync_watches()
+
+    def resume(self):
+        with self._state_lock:
+            self._paused = False
+        print("[MonitorService] Monitoring resumed")
+        self.sync_watches()
+
 
 # Global instance
 monitor_service = FileMonitorService()
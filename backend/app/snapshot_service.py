@@ -255,6 +255,10 @@ class SnapshotService:
         if thread and thread.is_alive():
             thread.join(timeout=2.0)
 
+    def is_running(self) -> bool:
+        with self._lock:
+            return bool(self._thread and self._thread.is_alive())
+
     def is_unlocked(self) -> bool:
         with self._lock:
             return self._fernet is not None
@@ -19,6 +19,11 @@ def parse_args() -> argparse.Namespace:
         default=os.getenv("LOCUS_DATA_DIR", ""),
         help="Optional data directory for LOCUS DB and snapshot files",
     )
+    parser.add_argument(
+        "--reload",
+        action="store_true",
+        help="Restart the backend when its sources change (development only)",
+    )
     return parser.parse_args()
 
 
@@ -34,6 +39,20 @@ def main() -> None:
     os.environ["LOCUS_HOST"] = str(args.host)
     os.environ["LOCUS_PORT"] = str(args.port)
 
+    if args.reload:
+        # Lets /system/shutdown stop the reloader instead of a worker it would respawn.
+        os.environ["LOCUS_RELOAD_SUPERVISOR_PID"] = str(os.getpid())
+        uvicorn.run(
+            "app.main:app",
+            host=args.host,
+            port=args.port,
+            reload=True,
+            reload_dirs=[str(backend_dir)],
+        )
+        return
+
+    # Only a reloader started right here may be signalled; never trust an inherited pid.
+    os.environ.pop("LOCUS_RELOAD_SUPERVISOR_PID", None)
     uvicorn.run("app.main:app", host=args.host, port=args.port, reload=False)
 
 
@@ -1,8 +1,14 @@
 from pathlib import Path
 import json
+import os
+import socket
+import threading
+import time
 import pytest
+from sqlalchemy import text
 
 from app import storage, event_stream
+from app import main as main_app
 from app.database import crud
 
 pytestmark = pytest.mark.slow
@@ -15,6 +21,43 @@ def test_health_check(client):
     assert data["db"] == "connected"
 
 
+def test_health_check_reports_component_status(client, monkeypatch):
+    monkeypatch.setattr(main_app.monitor_service, "is_running", lambda: True)
+    monkeypatch.setattr(main_app.snapshot_service, "is_running", lambda: False)
+
+    resp = client.get("/health")
+    assert resp.status_code == 200
+    assert resp.json()["components"] == {
+        "db": "ready",
+        "watcher": "ready",
+        "snapshot_engine": "stopped",
+    }
+
+
+def test_health_check_reports_versions(client):
+    resp = client.get("/health")
+    assert resp.status_code == 200
+    assert resp.json()["version"] == {
+        "app": main_app.APP_VERSION,
+        "api": main_app.API_VERSION,
+        "schema": main_app.SCHEMA_VERSION,
+    }
+
+
+def test_init_db_refuses_newer_schema(test_sessionmaker):
+    with main_app.engine.begin() as conn:
+        conn.execute(text(f"PRAGMA user_version={main_app.SCHEMA_VERSION + 1}"))
+
+    with pytest.raises(main_app.SchemaVersionError):
+        main_app.init_db()
+
+
+def test_init_db_records_schema_version(test_sessionmaker):
+    main_app.init_db()
+    with main_app.engine.connect() as conn:
+        assert main_app._read_schema_version(conn) == main_app.SCHEMA_VERSION
+
+
 def test_security_headers_present_on_api_responses(client):
     resp = client.get("/health")
     assert resp.status_code == 200
@@ -26,6 +69,77 @@ def test_security_headers_present_on_api_responses(client):
     assert "content-security-policy" in resp.headers
 
 
+def test_api_token_required_when_configured(client, monkeypatch):
+    monkeypatch.setenv("LOCUS_API_TOKEN", "launch-secret")
+
+    assert client.get("/health").status_code == 401
+    assert client.get("/health", headers={"X-Locus-Token": "wrong"}).status_code == 401
+    assert (
+        client.get("/health", headers={"X-Locus-Token": "launch-secret"}).status_code
+        == 200
+    )
+    assert client.get("/health?locus_token=launch-secret").status_code == 200
+
+
+def test_take_inherited_listener_adopts_passed_socket(monkeypatch):
+    listener = socket.socket(socket.AF_INET, socket.SOCK_STREAM)
+    listener.bind(("127.0.0.1", 0))
+    listener.listen()
+    monkeypatch.setenv("LISTEN_FDS", "1")
+    monkeypatch.setenv("LISTEN_FDNAMES", "locus-api")
+
+    try:
+        inherited = main_app._take_inherited_listener(os.dup(listener.fileno()))
+        assert inherited is not None
+        assert inherited.getsockname() == listener.getsockname()
+        assert "LISTEN_FDS" not in os.environ
+        assert "LISTEN_FDNAMES" not in os.environ
+        inherited.close()
+    finally:
+        listener.close()
+
+
+def test_take_inherited_listener_ignores_missing_handoff(monkeypatch):
+    monkeypatch.delenv("LISTEN_FDS", raising=False)
+    assert main_app._take_inherited_listener() is None
+
+
+def test_shutdown_request_drains_flushes_and_stops_server(client, monkeypatch):
+    calls = []
+    monkeypatch.setattr(main_app, "_shutdown_started", threading.Event())
+    monkeypatch.setattr(main_app, "_flush_database", lambda: calls.append("flush"))
+    monkeypatch.setattr(main_app, "_request_server_exit", lambda: calls.append("exit"))
+    monkeypatch.setenv("LOCUS_API_TOKEN", "launch-secret")
+
+    response = client.post(
+        "/system/shutdown",
+        json={"drain_timeout_seconds": 0},
+        headers={"X-Locus-Token": "launch-secret"},
+    )
+    assert response.status_code == 202
+    assert response.json() == {"status": "draining"}
+
+    deadline = time.monotonic() + 5
+    while len(calls) < 2 and time.monotonic() < deadline:
+        time.sleep(0.05)
+    assert calls == ["flush", "exit"]
+
+
+def test_shutdown_refused_without_launch_token(client, monkeypatch):
+    monkeypatch.setattr(main_app, "_shutdown_started", threading.Event())
+    monkeypatch.delenv("LOCUS_API_TOKEN", raising=False)
+
+    response = client.post("/system/shutdown", json={"drain_timeout_seconds": 0})
+    assert response.status_code == 403
+    assert not main_app._shutdown_started.is_set()
+
+
+def test_shutdown_drain_reports_tasks_left_at_deadline(client, db_session):
+    assert main_app._wait_for_backup_queue_drain(0) == 0
+    crud.enqueue_backup_task(db_session, "/tmp/pending.txt")
+    assert main_app._wait_for_backup_queue_drain(0) == 1
+
+
 def test_rejects_control_chars_in_user_input(client):
     bad_path = "C:/tracked/evil\u0000folder"
     resp = client.post("/files/watched", json={"path": bad_path})
@@ -53,6 +167,29 @@ def test_watched_paths_roundtrip(client, tmp_path: Path):
     assert str(watched) in paths
 
 
+def test_monitoring_pause_and_resume(client, monkeypatch):
+    monkeypatch.setattr(main_app.monitor_service, "_paused", False)
+    assert client.get("/monitoring").json() == {"paused": False}
+
+    resp = client.post("/monitoring/pause")
+    assert resp.status_code == 200
+    assert client.get("/monitoring").json() == {"paused": True}
+
+    resp = client.post("/monitoring/resume")
+    assert resp.status_code == 200
+    assert client.get("/monitoring").json() == {"paused": False}
+
+
+def test_snapshot_settings_toggle_enabled(client):
+    resp = client.post("/settings/snapshots", json={"enabled": True})
+    assert resp.status_code == 200
+    assert resp.json()["enabled"] is True
+
+    resp = client.post("/settings/snapshots", json={"enabled": False})
+    assert resp.status_code == 200
+    assert client.get("/settings/snapshots").json()["enabled"] is False
+
+
 def test_add_watched_path_is_idempotent(client, tmp_path: Path):
     watched = tmp_path / "watched"
     watched.mkdir()
@@ -221,6 +358,24 @@ def test_file_versions_and_current_version(client, db_session, tmp_path: Path):
     assert data["version_number"] == 1
 
 
+def test_file_versions_read_only_lookup_records_nothing(
+    client, db_session, tmp_path: Path
+):
+    watched = tmp_path / "watched"
+    watched.mkdir()
+    client.post("/files/watched", json={"path": str(watched)})
+
+    file_path = watched / "untracked.txt"
+    file_path.write_text("never backed up")
+
+    resp = client.get(
+        "/files/versions", params={"path": str(file_path), "create": "false"}
+    )
+    assert resp.status_code == 200
+    assert resp.json() == []
+    assert crud.get_file_record(db_session, str(file_path)) is None
+
+
 def test_file_query_endpoints_reject_paths_outside_watched(client, tmp_path: Path):
     watched = tmp_path / "watched"
     watched.mkdir()
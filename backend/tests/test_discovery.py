@@ -0,0 +1,41 @@
+import json
+import os
+
+from app import discovery
+
+
+def test_discovery_file_skipped_without_explicit_data_dir(monkeypatch):
+    monkeypatch.delenv("LOCUS_DATA_DIR", raising=False)
+    assert discovery.write_discovery_file("127.0.0.1", 8000, "1.5.0") is None
+
+
+def test_discovery_file_skipped_for_socket_mode_backend(monkeypatch, tmp_path):
+    monkeypatch.setenv("LOCUS_DATA_DIR", str(tmp_path))
+    monkeypatch.setenv("LOCUS_UDS", str(tmp_path / "run" / "backend.sock"))
+    assert discovery.write_discovery_file("127.0.0.1", 8000, "1.5.0") is None
+    assert not (tmp_path / discovery.DISCOVERY_FILE_NAME).exists()
+
+
+def test_discovery_file_round_trip(monkeypatch, tmp_path):
+    monkeypatch.setenv("LOCUS_DATA_DIR", str(tmp_path))
+
+    path = discovery.write_discovery_file("127.0.0.1", 8011, "1.5.0")
+    assert path == os.path.join(str(tmp_path), discovery.DISCOVERY_FILE_NAME)
+
+    with open(path, "r", encoding="utf-8") as handle:
+        record = json.load(handle)
+    assert record["pid"] == os.getpid()
+    assert record["port"] == 8011
+    assert record["data_dir"] == str(tmp_path)
+
+    discovery.remove_discovery_file()
+    assert not os.path.exists(path)
+
+
+def test_remove_keeps_record_owned_by_another_backend(monkeypatch, tmp_path):
+    monkeypatch.setenv("LOCUS_DATA_DIR", str(tmp_path))
+    path = tmp_path / discovery.DISCOVERY_FILE_NAME
+    path.write_text(json.dumps({"pid": os.getpid() + 1, "port": 8000}), encoding="utf-8")
+
+    discovery.remove_discovery_file()
+    assert path.exists()
@@ -1,3 +1,46 @@
+use std::fmt::Write;
+use std::path::Path;
+
+use sha2::{Digest, Sha256};
+
+// Digests of the bundled sidecars, checked by the shell before it spawns them.
+const SIDECARS: &[(&str, &str)] = &[
+    ("locus-backend", "LOCUS_BACKEND_SHA256"),
+    ("locus-window-probe", "LOCUS_WINDOW_PROBE_SHA256"),
+];
+
+fn sidecar_digest(name: &str, target: &str) -> String {
+    let extension = if target.contains("windows") {
+        ".exe"
+    } else {
+        ""
+    };
+    let path = Path::new("binaries").join(format!("{}-{}{}", name, target, extension));
+    println!("cargo:rerun-if-changed={}", path.display());
+
+    match std::fs::read(&path) {
+        Ok(bytes) => {
+            Sha256::digest(bytes)
+                .iter()
+                .fold(String::with_capacity(64), |mut hex, byte| {
+                    let _ = write!(hex, "{:02x}", byte);
+                    hex
+                })
+        }
+        // Left empty; release builds then refuse to spawn the sidecar at all.
+        Err(_) => String::new(),
+    }
+}
+
 fn main() {
+    let target = std::env::var("TARGET").unwrap_or_default();
+    for (name, env_key) in SIDECARS {
+        println!(
+            "cargo:rustc-env={}={}",
+            env_key,
+            sidecar_digest(name, &target)
+        );
+    }
+
     tauri_build::build()
 }
@@ -0,0 +1,40 @@
+use std::fmt::Write;
+use std::sync::OnceLock;
+
+pub const API_TOKEN_ENV: &str = "LOCUS_API_TOKEN";
+pub const API_TOKEN_HEADER: &str = "X-Locus-Token";
+
+const API_TOKEN_BYTES: usize = 32;
+
+static API_TOKEN: OnceLock<String> = OnceLock::new();
+
+fn generate_api_token() -> String {
+    let mut bytes = [0u8; API_TOKEN_BYTES];
+    getrandom::getrandom(&mut bytes).expect("OS random source unavailable for API token");
+    bytes.iter().fold(
+        String::with_capacity(API_TOKEN_BYTES * 2),
+        |mut hex, byte| {
+            let _ = write!(hex, "{:02x}", byte);
+            hex
+        },
+    )
+}
+
+// One secret per shell launch; restarted sidecars reuse it so open webviews keep working.
+pub fn api_token() -> &'static str {
+    API_TOKEN.get_or_init(generate_api_token)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn token_is_hex_and_stable_for_the_launch() {
+        let token = api_token();
+        assert_eq!(token.len(), API_TOKEN_BYTES * 2);
+        assert!(token.chars().all(|ch| ch.is_ascii_hexdigit()));
+        assert_eq!(api_token(), token);
+        assert_ne!(generate_api_token(), token);
+    }
+}
@@ -0,0 +1,244 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::Child;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use crate::resolve_locus_data_dir;
+
+const BACKEND_LOG_DIR_NAME: &str = "logs";
+const BACKEND_LOG_FILE_NAME: &str = "backend.log";
+pub const BACKEND_LOG_MAX_BYTES: u64 = 5 * 1024 * 1024;
+pub const BACKEND_LOG_RETENTION: usize = 5;
+const BACKEND_LOG_TAIL_DEFAULT_LINES: usize = 200;
+const BACKEND_LOG_TAIL_MAX_LINES: usize = 5000;
+
+// Also backs the shell's own log, see logging.rs.
+pub struct RotatingLogFile {
+    dir: PathBuf,
+    file_name: &'static str,
+    max_bytes: u64,
+    retention: usize,
+    file: File,
+    written: u64,
+}
+
+impl RotatingLogFile {
+    pub fn open(
+        dir: &Path,
+        file_name: &'static str,
+        max_bytes: u64,
+        retention: usize,
+    ) -> io::Result<Self> {
+        fs::create_dir_all(dir)?;
+        prune_archives(dir, file_name, retention);
+
+        let path = dir.join(file_name);
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let written = file.metadata().map(|meta| meta.len()).unwrap_or(0);
+
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            file_name,
+            max_bytes,
+            retention,
+            file,
+            written,
+        })
+    }
+
+    pub fn write_line(&mut self, line: &[u8]) -> io::Result<()> {
+        if self.written > 0 && self.written + line.len() as u64 > self.max_bytes {
+            self.rotate()?;
+        }
+
+        self.file.write_all(line)?;
+        if !line.ends_with(b"\n") {
+            self.file.write_all(b"\n")?;
+            self.written += 1;
+        }
+        self.written += line.len() as u64;
+        self.file.flush()
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        let current = self.dir.join(self.file_name);
+
+        if self.retention == 0 {
+            self.file = File::create(&current)?;
+            self.written = 0;
+            return Ok(());
+        }
+
+        let _ = fs::remove_file(archive_path(&self.dir, self.file_name, self.retention));
+        for index in (1..self.retention).rev() {
+            let from = archive_path(&self.dir, self.file_name, index);
+            if from.exists() {
+                let _ = fs::rename(&from, archive_path(&self.dir, self.file_name, index + 1));
+            }
+        }
+        fs::rename(&current, archive_path(&self.dir, self.file_name, 1))?;
+
+        self.file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&current)?;
+        self.written = 0;
+        Ok(())
+    }
+}
+
+fn archive_path(dir: &Path, file_name: &str, index: usize) -> PathBuf {
+    dir.join(format!("{}.{}", file_name, index))
+}
+
+fn prune_archives(dir: &Path, file_name: &str, retention: usize) {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return,
+    };
+
+    let prefix = format!("{}.", file_name);
+    for entry in entries.flatten() {
+        let name = entry.file_name();
+        let index = name
+            .to_str()
+            .and_then(|name| name.strip_prefix(prefix.as_str()))
+            .and_then(|suffix| suffix.parse::<usize>().ok());
+        if matches!(index, Some(index) if index > retention) {
+            let _ = fs::remove_file(entry.path());
+        }
+    }
+}
+
+pub fn backend_log_dir(data_dir: &Path) -> PathBuf {
+    data_dir.join(BACKEND_LOG_DIR_NAME)
+}
+
+fn pump_stream<R: Read + Send + 'static>(stream: R, sink: Arc<Mutex<RotatingLogFile>>) {
+    thread::spawn(move || {
+        let mut reader = BufReader::new(stream);
+        let mut line = Vec::new();
+        loop {
+            line.clear();
+            match reader.read_until(b'\n', &mut line) {
+                Ok(0) | Err(_) => break,
+                Ok(_) => {
+                    if let Ok(mut sink) = sink.lock() {
+                        let _ = sink.write_line(&line);
+                    }
+                }
+            }
+        }
+    });
+}
+
+fn discard_stream<R: Read + Send + 'static>(mut stream: R) {
+    thread::spawn(move || {
+        let _ = io::copy(&mut stream, &mut io::sink());
+    });
+}
+
+// Both streams share one file so stdout and stderr lines stay interleaved in order.
+pub fn capture_backend_output(child: &mut Child, log_dir: &Path) -> io::Result<()> {
+    let settings = crate::config::shell_config();
+    let sink = match RotatingLogFile::open(
+        log_dir,
+        BACKEND_LOG_FILE_NAME,
+        settings.logs.max_bytes,
+        settings.logs.retention,
+    ) {
+        Ok(log) => Arc::new(Mutex::new(log)),
+        Err(err) => {
+            // Keep draining the pipes so a chatty backend never blocks on a full buffer.
+            if let Some(stdout) = child.stdout.take() {
+                discard_stream(stdout);
+            }
+            if let Some(stderr) = child.stderr.take() {
+                discard_stream(stderr);
+            }
+            return Err(err);
+        }
+    };
+
+    if let Some(stdout) = child.stdout.take() {
+        pump_stream(stdout, Arc::clone(&sink));
+    }
+    if let Some(stderr) = child.stderr.take() {
+        pump_stream(stderr, sink);
+    }
+
+    Ok(())
+}
+
+fn read_lines(path: &Path) -> Vec<String> {
+    match fs::read(path) {
+        Ok(bytes) => String::from_utf8_lossy(&bytes)
+            .lines()
+            .map(str::to_string)
+            .collect(),
+        Err(_) => Vec::new(),
+    }
+}
+
+pub fn tail_backend_log(log_dir: &Path, max_lines: usize) -> Vec<String> {
+    let mut lines = read_lines(&log_dir.join(BACKEND_LOG_FILE_NAME));
+
+    // Right after a rotation the current file is nearly empty; borrow from the last archive.
+    if lines.len() < max_lines {
+        let mut previous = read_lines(&archive_path(log_dir, BACKEND_LOG_FILE_NAME, 1));
+        previous.append(&mut lines);
+        lines = previous;
+    }
+
+    let skip = lines.len().saturating_sub(max_lines);
+    lines.split_off(skip)
+}
+
+#[tauri::command]
+pub fn get_backend_log_tail(max_lines: Option<usize>) -> Vec<String> {
+    let max_lines = max_lines
+        .unwrap_or(BACKEND_LOG_TAIL_DEFAULT_LINES)
+        .clamp(1, BACKEND_LOG_TAIL_MAX_LINES);
+    tail_backend_log(&backend_log_dir(&resolve_locus_data_dir()), max_lines)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::scratch_dir;
+
+    #[test]
+    fn rotation_keeps_only_the_configured_archives() {
+        let dir = scratch_dir("log-rotation");
+        let mut log =
+            RotatingLogFile::open(&dir, BACKEND_LOG_FILE_NAME, 16, 2).expect("failed to open log");
+
+        for index in 0..6 {
+            log.write_line(format!("line-{:02}-abcdef\n", index).as_bytes())
+                .expect("write failed");
+        }
+
+        assert!(dir.join(BACKEND_LOG_FILE_NAME).exists());
+        assert!(archive_path(&dir, BACKEND_LOG_FILE_NAME, 1).exists());
+        assert!(archive_path(&dir, BACKEND_LOG_FILE_NAME, 2).exists());
+        assert!(!archive_path(&dir, BACKEND_LOG_FILE_NAME, 3).exists());
+        assert_eq!(tail_backend_log(&dir, 1), vec!["line-05-abcdef"]);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn tail_reaches_into_the_previous_archive() {
+        let dir = scratch_dir("log-tail");
+        fs::write(archive_path(&dir, BACKEND_LOG_FILE_NAME, 1), "a\nb\nc\n")
+            .expect("write archive failed");
+        fs::write(dir.join(BACKEND_LOG_FILE_NAME), "d\n").expect("write log failed");
+
+        assert_eq!(tail_backend_log(&dir, 3), vec!["b", "c", "d"]);
+        assert_eq!(tail_backend_log(&dir, 1), vec!["d"]);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,456 @@
+use std::fs;
+use std::io::{self, Read, Write};
+use std::net::{SocketAddr, TcpStream};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::Duration;
+
+#[cfg(unix)]
+use std::io::{BufRead, BufReader};
+#[cfg(unix)]
+use std::os::unix::net::UnixStream;
+#[cfg(unix)]
+use std::sync::atomic::Ordering;
+#[cfg(unix)]
+use std::thread;
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use serde::Serialize;
+use serde_json::Value;
+use tauri::AppHandle;
+#[cfg(unix)]
+use tauri::{Manager, State};
+#[cfg(unix)]
+use tracing::warn;
+
+use crate::api_token::{api_token, API_TOKEN_HEADER};
+#[cfg(unix)]
+use crate::BackendState;
+
+pub const BACKEND_TRANSPORT_ENV: &str = "LOCUS_BACKEND_TRANSPORT";
+pub const BACKEND_SOCKET_ENV: &str = "LOCUS_UDS";
+// Sentinel base URL injected into the webview; api.js routes it through proxy_backend_request.
+pub const PROXY_BASE_URL: &str = "locus-socket://backend";
+pub const FILE_EVENT: &str = "locus://file-event";
+
+const SOCKET_DIR_NAME: &str = "run";
+const SOCKET_FILE_NAME: &str = "backend.sock";
+const FILE_EVENT_STREAM_PATH: &str = "/files/events/stream";
+const PROXY_REQUEST_TIMEOUT_SECS: u64 = 120;
+const JSON_REQUEST_TIMEOUT_MS: u64 = 2000;
+const EVENT_STREAM_READ_TIMEOUT_SECS: u64 = 45;
+const EVENT_BRIDGE_RECONNECT_MS: u64 = 1000;
+const RESPONSE_MAX_BYTES: u64 = 256 * 1024 * 1024;
+
+static SOCKET_PATH: OnceLock<Option<PathBuf>> = OnceLock::new();
+
+pub struct SocketResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ProxiedResponse {
+    status: u16,
+    headers: Vec<(String, String)>,
+    body: String,
+}
+
+#[cfg(target_os = "linux")]
+fn resolve_socket_path() -> Option<PathBuf> {
+    let transport = std::env::var(BACKEND_TRANSPORT_ENV).unwrap_or_default();
+    if !transport.trim().eq_ignore_ascii_case("unix") {
+        return None;
+    }
+    Some(
+        crate::resolve_locus_data_dir()
+            .join(SOCKET_DIR_NAME)
+            .join(SOCKET_FILE_NAME),
+    )
+}
+
+#[cfg(not(target_os = "linux"))]
+fn resolve_socket_path() -> Option<PathBuf> {
+    None
+}
+
+// Opt-in (Linux only): with LOCUS_BACKEND_TRANSPORT=unix the backend binds no TCP port.
+pub fn unix_socket_path() -> Option<&'static Path> {
+    SOCKET_PATH.get_or_init(resolve_socket_path).as_deref()
+}
+
+#[cfg(unix)]
+pub fn prepare_socket_dir(socket_path: &Path) -> io::Result<()> {
+    use std::os::unix::fs::{DirBuilderExt, PermissionsExt};
+
+    let dir = socket_path
+        .parent()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "socket path has no parent"))?;
+    fs::DirBuilder::new()
+        .recursive(true)
+        .mode(0o700)
+        .create(dir)?;
+    // DirBuilder leaves an existing directory's mode alone, and the directory is the
+    // only thing guarding the socket (uvicorn makes the socket itself world-writable).
+    fs::set_permissions(dir, fs::Permissions::from_mode(0o700))?;
+
+    match fs::remove_file(socket_path) {
+        Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(not(unix))]
+pub fn prepare_socket_dir(_socket_path: &Path) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "unix socket transport is not supported on this platform",
+    ))
+}
+
+fn is_forwardable_header(name: &str) -> bool {
+    !matches!(
+        name.to_ascii_lowercase().as_str(),
+        "host" | "connection" | "content-length" | "transfer-encoding" | "x-locus-token"
+    )
+}
+
+fn write_request(
+    stream: &mut impl Write,
+    method: &str,
+    target: &str,
+    headers: &[(String, String)],
+    body: &[u8],
+) -> io::Result<()> {
+    let mut head = format!(
+        "{} {} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n{}: {}\r\nContent-Length: {}\r\n",
+        method,
+        target,
+        API_TOKEN_HEADER,
+        api_token(),
+        body.len()
+    );
+    for (name, value) in headers {
+        if is_forwardable_header(name)
+            && !name.contains(['\r', '\n'])
+            && !value.contains(['\r', '\n'])
+        {
+            head.push_str(&format!("{}: {}\r\n", name, value));
+        }
+    }
+    head.push_str("\r\n");
+
+    stream.write_all(head.as_bytes())?;
+    stream.write_all(body)?;
+    stream.flush()
+}
+
+fn invalid_response(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
+
+fn decode_chunked(mut raw: &[u8]) -> io::Result<Vec<u8>> {
+    let mut decoded = Vec::new();
+    loop {
+        let line_end = raw
+            .windows(2)
+            .position(|window| window == b"\r\n")
+            .ok_or_else(|| invalid_response("truncated chunk header"))?;
+        let size_field = std::str::from_utf8(&raw[..line_end])
+            .map_err(|_| invalid_response("invalid chunk header"))?;
+        let size_hex = size_field.split(';').next().unwrap_or("").trim();
+        let size = usize::from_str_radix(size_hex, 16)
+            .map_err(|_| invalid_response("invalid chunk size"))?;
+        raw = &raw[line_end + 2..];
+        if size == 0 {
+            return Ok(decoded);
+        }
+        if raw.len() < size + 2 {
+            return Err(invalid_response("truncated chunk"));
+        }
+        decoded.extend_from_slice(&raw[..size]);
+        raw = &raw[size + 2..];
+    }
+}
+
+fn parse_response(raw: &[u8]) -> io::Result<SocketResponse> {
+    let header_end = raw
+        .windows(4)
+        .position(|window| window == b"\r\n\r\n")
+        .ok_or_else(|| invalid_response("missing response headers"))?;
+    let head = std::str::from_utf8(&raw[..header_end])
+        .map_err(|_| invalid_response("response headers are not UTF-8"))?;
+
+    let mut lines = head.split("\r\n");
+    let status = lines
+        .next()
+        .and_then(|line| line.split_whitespace().nth(1))
+        .and_then(|code| code.parse::<u16>().ok())
+        .ok_or_else(|| invalid_response("malformed status line"))?;
+
+    let mut headers = Vec::new();
+    let mut chunked = false;
+    for line in lines {
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        let (name, value) = (name.trim(), value.trim());
+        if name.eq_ignore_ascii_case("transfer-encoding") {
+            chunked = value.eq_ignore_ascii_case("chunked");
+            continue;
+        }
+        if name.eq_ignore_ascii_case("connection") || name.eq_ignore_ascii_case("content-length") {
+            continue;
+        }
+        headers.push((name.to_string(), value.to_string()));
+    }
+
+    let body = &raw[header_end + 4..];
+    let body = if chunked {
+        decode_chunked(body)?
+    } else {
+        body.to_vec()
+    };
+    Ok(SocketResponse {
+        status,
+        headers,
+        body,
+    })
+}
+
+#[cfg(unix)]
+pub fn send_request(
+    socket_path: &Path,
+    method: &str,
+    target: &str,
+    headers: &[(String, String)],
+    body: &[u8],
+    timeout: Duration,
+) -> io::Result<SocketResponse> {
+    let mut stream = UnixStream::connect(socket_path)?;
+    stream.set_read_timeout(Some(timeout))?;
+    stream.set_write_timeout(Some(timeout))?;
+    write_request(&mut stream, method, target, headers, body)?;
+
+    let mut response = Vec::new();
+    stream.take(RESPONSE_MAX_BYTES).read_to_end(&mut response)?;
+    parse_response(&response)
+}
+
+#[cfg(not(unix))]
+pub fn send_request(
+    _socket_path: &Path,
+    _method: &str,
+    _target: &str,
+    _headers: &[(String, String)],
+    _body: &[u8],
+    _timeout: Duration,
+) -> io::Result<SocketResponse> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "unix socket transport is not supported on this platform",
+    ))
+}
+
+// Shell-originated API calls: over the socket in socket mode, loopback TCP otherwise.
+pub fn send_backend_request(
+    port: u16,
+    method: &str,
+    target: &str,
+    headers: &[(String, String)],
+    body: &[u8],
+    timeout: Duration,
+) -> io::Result<SocketResponse> {
+    if let Some(socket_path) = unix_socket_path() {
+        return send_request(socket_path, method, target, headers, body, timeout);
+    }
+
+    let addr = SocketAddr::from(([127, 0, 0, 1], port));
+    let mut stream = TcpStream::connect_timeout(&addr, timeout)?;
+    stream.set_read_timeout(Some(timeout))?;
+    stream.set_write_timeout(Some(timeout))?;
+    write_request(&mut stream, method, target, headers, body)?;
+
+    let mut response = Vec::new();
+    stream.take(RESPONSE_MAX_BYTES).read_to_end(&mut response)?;
+    parse_response(&response)
+}
+
+// For the shell's own small API calls. Errors carry FastAPI's `detail` when there is one,
+// so they can be shown to the user as they are.
+pub fn request_backend_json(
+    port: u16,
+    method: &str,
+    target: &str,
+    body: Option<Value>,
+) -> Result<Value, String> {
+    let headers = [("Content-Type".to_string(), "application/json".to_string())];
+    let body = body.map(|body| body.to_string()).unwrap_or_default();
+    let response = send_backend_request(
+        port,
+        method,
+        target,
+        &headers,
+        body.as_bytes(),
+        Duration::from_millis(JSON_REQUEST_TIMEOUT_MS),
+    )
+    .map_err(|err| format!("could not reach the backend: {}", err))?;
+    let parsed = serde_json::from_slice::<Value>(&response.body);
+    if !(200..300).contains(&response.status) {
+        let detail = parsed
+            .ok()
+            .and_then(|body| body.get("detail")?.as_str().map(str::to_string));
+        return Err(match detail {
+            Some(detail) => detail,
+            None => format!("{} {} returned HTTP {}", method, target, response.status),
+        });
+    }
+    parsed.map_err(|err| format!("{} {} returned invalid JSON: {}", method, target, err))
+}
+
+// Webview API traffic in socket mode. Bodies travel base64-encoded so snapshot images
+// survive the JSON IPC boundary.
+#[tauri::command]
+pub async fn proxy_backend_request(
+    method: String,
+    path: String,
+    headers: Vec<(String, String)>,
+    body: Option<String>,
+) -> Result<ProxiedResponse, String> {
+    let socket_path = unix_socket_path().ok_or("Backend socket transport is not enabled")?;
+    if !path.starts_with('/') {
+        return Err(format!("Proxy path must be absolute: {}", path));
+    }
+    if path.split('?').next() == Some(FILE_EVENT_STREAM_PATH) {
+        return Err(format!("Subscribe to {} instead of streaming", FILE_EVENT));
+    }
+
+    let method = method.to_ascii_uppercase();
+    let body = body.unwrap_or_default().into_bytes();
+    let timeout = Duration::from_secs(PROXY_REQUEST_TIMEOUT_SECS);
+    let response = tauri::async_runtime::spawn_blocking(move || {
+        send_request(socket_path, &method, &path, &headers, &body, timeout)
+    })
+    .await
+    .map_err(|err| err.to_string())?
+    .map_err(|err| format!("Backend socket request failed: {}", err))?;
+
+    Ok(ProxiedResponse {
+        status: response.status,
+        headers: response.headers,
+        body: BASE64.encode(response.body),
+    })
+}
+
+// uvicorn writes one chunk per SSE event, so the chunk-size lines interleaved with the
+// event lines never split a `data:` line and can simply be skipped.
+#[cfg(unix)]
+fn pump_file_events(app: &AppHandle, socket_path: &Path) -> io::Result<()> {
+    let mut stream = UnixStream::connect(socket_path)?;
+    stream.set_read_timeout(Some(Duration::from_secs(EVENT_STREAM_READ_TIMEOUT_SECS)))?;
+    let accept = vec![("Accept".to_string(), "text/event-stream".to_string())];
+    write_request(&mut stream, "GET", FILE_EVENT_STREAM_PATH, &accept, &[])?;
+
+    for line in BufReader::new(stream).lines() {
+        let line = line?;
+        if let Some(data) = line.strip_prefix("data:") {
+            if let Ok(event) = serde_json::from_str::<serde_json::Value>(data.trim()) {
+                let _ = app.emit_all(FILE_EVENT, event);
+            }
+        }
+    }
+    Ok(())
+}
+
+// EventSource cannot go through the IPC proxy, so the shell relays the stream as events.
+#[cfg(unix)]
+pub fn start_file_event_bridge(app: AppHandle) {
+    let Some(socket_path) = unix_socket_path() else {
+        return;
+    };
+
+    thread::spawn(move || loop {
+        let state: State<BackendState> = app.state();
+        if state.shutting_down.load(Ordering::SeqCst) {
+            return;
+        }
+        if let Err(err) = pump_file_events(&app, socket_path) {
+            if err.kind() != io::ErrorKind::ConnectionRefused
+                && err.kind() != io::ErrorKind::NotFound
+            {
+                warn!(error = %err, "file event bridge disconnected");
+            }
+        }
+        thread::sleep(Duration::from_millis(EVENT_BRIDGE_RECONNECT_MS));
+    });
+}
+
+#[cfg(not(unix))]
+pub fn start_file_event_bridge(_app: AppHandle) {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_chunked_response_and_drops_hop_headers() {
+        let raw = b"HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ntransfer-encoding: chunked\r\nconnection: close\r\n\r\n4\r\n{\"a\"\r\n3\r\n:1}\r\n0\r\n\r\n";
+        let response = parse_response(raw).expect("response should parse");
+
+        assert_eq!(response.status, 200);
+        assert_eq!(
+            response.headers,
+            vec![("content-type".to_string(), "application/json".to_string())]
+        );
+        assert_eq!(response.body, b"{\"a\":1}");
+    }
+
+    #[test]
+    fn request_carries_token_and_strips_webview_hop_headers() {
+        let mut written = Vec::new();
+        let headers = vec![
+            ("Content-Type".to_string(), "application/json".to_string()),
+            ("Host".to_string(), "evil".to_string()),
+            ("X-Locus-Token".to_string(), "stale".to_string()),
+        ];
+        write_request(&mut written, "POST", "/files/watched", &headers, b"{}")
+            .expect("writing to a Vec cannot fail");
+        let text = String::from_utf8(written).expect("request should be UTF-8");
+
+        assert!(text.starts_with("POST /files/watched HTTP/1.1\r\n"));
+        assert!(text.contains(&format!("{}: {}\r\n", API_TOKEN_HEADER, api_token())));
+        assert!(text.contains("Content-Type: application/json\r\n"));
+        assert!(text.contains("Content-Length: 2\r\n"));
+        assert!(!text.contains("evil"));
+        assert!(!text.contains("stale"));
+        assert!(text.ends_with("\r\n\r\n{}"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn socket_dir_is_private_and_stale_socket_removed() {
+        use crate::test_support::scratch_dir;
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = scratch_dir("backend-socket");
+        let socket_dir = dir.join(SOCKET_DIR_NAME);
+        let socket_path = socket_dir.join(SOCKET_FILE_NAME);
+        fs::create_dir_all(&socket_dir).expect("failed to create socket dir");
+        fs::set_permissions(&socket_dir, fs::Permissions::from_mode(0o755))
+            .expect("failed to open up socket dir");
+        fs::write(&socket_path, b"stale").expect("failed to write stale socket");
+
+        prepare_socket_dir(&socket_path).expect("prepare should succeed");
+
+        let mode = fs::metadata(&socket_dir)
+            .expect("socket dir should remain")
+            .permissions()
+            .mode();
+        assert_eq!(mode & 0o777, 0o700);
+        assert!(!socket_path.exists());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
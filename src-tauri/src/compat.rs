@@ -0,0 +1,149 @@
+use std::fmt;
+
+use serde::Serialize;
+use serde_json::Value;
+
+pub const SHELL_VERSION: &str = env!("CARGO_PKG_VERSION");
+// Must match API_VERSION in backend/app/main.py.
+const BACKEND_API_VERSION: u64 = 1;
+// Oldest database schema this shell's UI knows how to present.
+const MIN_BACKEND_SCHEMA_VERSION: u64 = 1;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct BackendVersion {
+    pub app: String,
+    pub api: u64,
+    pub schema: u64,
+}
+
+impl BackendVersion {
+    pub fn parse(body: &Value) -> Option<Self> {
+        let version = body.get("version")?;
+        Some(Self {
+            app: version.get("app")?.as_str()?.to_string(),
+            api: version.get("api")?.as_u64()?,
+            schema: version.get("schema")?.as_u64()?,
+        })
+    }
+}
+
+impl fmt::Display for BackendVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (api {}, schema {})", self.app, self.api, self.schema)
+    }
+}
+
+#[derive(Debug)]
+pub enum Incompatibility {
+    Unversioned,
+    ApiMismatch(BackendVersion),
+    OlderBackend(BackendVersion),
+    OlderSchema(BackendVersion),
+}
+
+impl fmt::Display for Incompatibility {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unversioned => write!(
+                f,
+                "backend does not report its version; it predates app {}",
+                SHELL_VERSION
+            ),
+            Self::ApiMismatch(version) => write!(
+                f,
+                "backend {} speaks API {}, app {} needs API {}",
+                version, version.api, SHELL_VERSION, BACKEND_API_VERSION
+            ),
+            Self::OlderBackend(version) => {
+                write!(f, "backend {} is older than app {}", version, SHELL_VERSION)
+            }
+            Self::OlderSchema(version) => write!(
+                f,
+                "backend {} uses schema {}, app {} needs at least schema {}",
+                version, version.schema, SHELL_VERSION, MIN_BACKEND_SCHEMA_VERSION
+            ),
+        }
+    }
+}
+
+// Anything that is not plain major.minor.patch sorts as unknown and is not compared.
+fn parse_release(raw: &str) -> Option<(u64, u64, u64)> {
+    let core = raw.trim().split(['-', '+']).next()?;
+    let mut parts = core.split('.').map(|part| part.parse::<u64>().ok());
+    let release = (parts.next()??, parts.next()??, parts.next()??);
+    parts.next().is_none().then_some(release)
+}
+
+// A newer backend is fine as long as the API matches; an older one may lack endpoints
+// the UI calls unconditionally.
+pub fn check_backend_version(version: Option<&BackendVersion>) -> Result<(), Incompatibility> {
+    let version = version.ok_or(Incompatibility::Unversioned)?;
+    if version.api != BACKEND_API_VERSION {
+        return Err(Incompatibility::ApiMismatch(version.clone()));
+    }
+    if let (Some(backend), Some(shell)) =
+        (parse_release(&version.app), parse_release(SHELL_VERSION))
+    {
+        if backend < shell {
+            return Err(Incompatibility::OlderBackend(version.clone()));
+        }
+    }
+    if version.schema < MIN_BACKEND_SCHEMA_VERSION {
+        return Err(Incompatibility::OlderSchema(version.clone()));
+    }
+    Ok(())
+}
+
+pub fn describe_combination(version: Option<&BackendVersion>) -> String {
+    match version {
+        Some(version) => format!("app {}, backend {}", SHELL_VERSION, version),
+        None => format!("app {}, backend unknown", SHELL_VERSION),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn version(app: &str, api: u64, schema: u64) -> BackendVersion {
+        BackendVersion {
+            app: app.to_string(),
+            api,
+            schema,
+        }
+    }
+
+    #[test]
+    fn parses_version_block_from_health() {
+        let body: Value = serde_json::from_str(
+            r#"{"db":"connected","version":{"app":"1.5.0","api":1,"schema":1}}"#,
+        )
+        .expect("valid json");
+        assert_eq!(BackendVersion::parse(&body), Some(version("1.5.0", 1, 1)));
+
+        let legacy: Value = serde_json::from_str(r#"{"db":"connected"}"#).expect("valid json");
+        assert_eq!(BackendVersion::parse(&legacy), None);
+    }
+
+    #[test]
+    fn refuses_mismatched_or_older_backends() {
+        assert!(check_backend_version(Some(&version(SHELL_VERSION, 1, 1))).is_ok());
+        assert!(check_backend_version(Some(&version("99.0.0", 1, 1))).is_ok());
+        assert!(matches!(
+            check_backend_version(None),
+            Err(Incompatibility::Unversioned)
+        ));
+        assert!(matches!(
+            check_backend_version(Some(&version(SHELL_VERSION, 2, 1))),
+            Err(Incompatibility::ApiMismatch(_))
+        ));
+        assert!(matches!(
+            check_backend_version(Some(&version("0.9.0", 1, 1))),
+            Err(Incompatibility::OlderBackend(_))
+        ));
+        assert!(matches!(
+            check_backend_version(Some(&version(SHELL_VERSION, 1, 0))),
+            Err(Incompatibility::OlderSchema(_))
+        ));
+    }
+}
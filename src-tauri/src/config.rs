@@ -0,0 +1,432 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock, RwLock};
+
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::{
+    api_token, backend_socket, resolve_base_data_dir, BACKEND_POLL_INTERVAL_MS,
+    BACKEND_PORT_SEARCH_LIMIT, BACKEND_STARTUP_TIMEOUT_SECS, DEFAULT_BACKEND_PORT,
+};
+
+pub const CONFIG_FILE_NAME: &str = "locus.toml";
+
+// Set by the shell itself on every spawn; an override would break the handshake. The
+// reload pid names the process /system/shutdown signals, so only --reload may set it.
+const RESERVED_SIDECAR_ENV: &[&str] = &[
+    "LOCUS_PORT",
+    "LOCUS_DATA_DIR",
+    "LOCUS_PARENT_PID",
+    api_token::API_TOKEN_ENV,
+    backend_socket::BACKEND_SOCKET_ENV,
+    "LISTEN_FDS",
+    "LISTEN_FDNAMES",
+    "LISTEN_PID",
+    "LOCUS_RELOAD_SUPERVISOR_PID",
+];
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BackendConfig {
+    pub port: u16,
+    pub port_search_limit: u16,
+    pub startup_timeout_secs: u64,
+    pub poll_interval_ms: u64,
+    pub shutdown_deadline_secs: u64,
+    pub env: BTreeMap<String, String>,
+}
+
+impl Default for BackendConfig {
+    fn default() -> Self {
+        Self {
+            port: DEFAULT_BACKEND_PORT,
+            port_search_limit: BACKEND_PORT_SEARCH_LIMIT,
+            startup_timeout_secs: BACKEND_STARTUP_TIMEOUT_SECS,
+            poll_interval_ms: BACKEND_POLL_INTERVAL_MS,
+            shutdown_deadline_secs: crate::shutdown::DEFAULT_SHUTDOWN_DEADLINE_SECS,
+            env: BTreeMap::new(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PathsConfig {
+    pub data_dir: Option<PathBuf>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TrayConfig {
+    pub close_to_tray: bool,
+}
+
+impl Default for TrayConfig {
+    fn default() -> Self {
+        Self {
+            close_to_tray: true,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LogsConfig {
+    pub max_bytes: u64,
+    pub retention: usize,
+    pub level: String,
+}
+
+impl Default for LogsConfig {
+    fn default() -> Self {
+        Self {
+            max_bytes: crate::backend_logs::BACKEND_LOG_MAX_BYTES,
+            retention: crate::backend_logs::BACKEND_LOG_RETENTION,
+            level: crate::logging::DEFAULT_LOG_LEVEL.to_string(),
+        }
+    }
+}
+
+// `throttled` is the launch default; the tray toggle changes it for the session only.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PerformanceConfig {
+    pub throttled: bool,
+    pub nice: i32,
+    pub idle_io: bool,
+    pub cgroup: bool,
+    pub cpu_quota_percent: Option<u32>,
+    pub memory_max_mb: Option<u64>,
+}
+
+impl Default for PerformanceConfig {
+    fn default() -> Self {
+        Self {
+            throttled: false,
+            nice: 10,
+            idle_io: true,
+            cgroup: false,
+            cpu_quota_percent: None,
+            memory_max_mb: None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ShellConfig {
+    pub backend: BackendConfig,
+    pub paths: PathsConfig,
+    pub tray: TrayConfig,
+    pub logs: LogsConfig,
+    pub performance: PerformanceConfig,
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Read {
+        path: PathBuf,
+        source: io::Error,
+    },
+    Parse {
+        path: PathBuf,
+        message: String,
+    },
+    Invalid {
+        path: PathBuf,
+        key: &'static str,
+        message: String,
+    },
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Read { path, source } => {
+                write!(f, "failed to read '{}': {}", path.display(), source)
+            }
+            Self::Parse { path, message } => {
+                write!(
+                    f,
+                    "'{}' is not valid TOML: {}",
+                    path.display(),
+                    message.trim_end()
+                )
+            }
+            Self::Invalid { path, key, message } => {
+                write!(f, "'{}': {} {}", path.display(), key, message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+fn check_range<T: PartialOrd + fmt::Display>(
+    path: &Path,
+    key: &'static str,
+    value: T,
+    min: T,
+    max: T,
+) -> Result<(), ConfigError> {
+    if value < min || value > max {
+        return Err(ConfigError::Invalid {
+            path: path.to_path_buf(),
+            key,
+            message: format!("must be between {} and {}, got {}", min, max, value),
+        });
+    }
+    Ok(())
+}
+
+impl ShellConfig {
+    fn validate(&self, path: &Path) -> Result<(), ConfigError> {
+        let invalid = |key: &'static str, message: String| ConfigError::Invalid {
+            path: path.to_path_buf(),
+            key,
+            message,
+        };
+
+        check_range(path, "backend.port", self.backend.port, 1024, u16::MAX)?;
+        check_range(
+            path,
+            "backend.port_search_limit",
+            self.backend.port_search_limit,
+            0,
+            1000,
+        )?;
+        check_range(
+            path,
+            "backend.startup_timeout_secs",
+            self.backend.startup_timeout_secs,
+            1,
+            600,
+        )?;
+        check_range(
+            path,
+            "backend.poll_interval_ms",
+            self.backend.poll_interval_ms,
+            10,
+            5000,
+        )?;
+        check_range(
+            path,
+            "backend.shutdown_deadline_secs",
+            self.backend.shutdown_deadline_secs,
+            1,
+            300,
+        )?;
+        for key in self.backend.env.keys() {
+            if key.is_empty() || key.contains(['=', '\0']) {
+                return Err(invalid(
+                    "backend.env",
+                    format!("has an invalid variable name '{}'", key),
+                ));
+            }
+            if RESERVED_SIDECAR_ENV.contains(&key.as_str()) {
+                return Err(invalid(
+                    "backend.env",
+                    format!("cannot override '{}', the shell sets it itself", key),
+                ));
+            }
+        }
+
+        if let Some(data_dir) = &self.paths.data_dir {
+            if !data_dir.is_absolute() {
+                return Err(invalid(
+                    "paths.data_dir",
+                    format!("must be an absolute path, got '{}'", data_dir.display()),
+                ));
+            }
+        }
+
+        check_range(
+            path,
+            "logs.max_bytes",
+            self.logs.max_bytes,
+            64 * 1024,
+            1024 * 1024 * 1024,
+        )?;
+        check_range(path, "logs.retention", self.logs.retention, 0, 100)?;
+        if crate::logging::parse_level(&self.logs.level).is_none() {
+            return Err(invalid(
+                "logs.level",
+                format!(
+                    "must be one of error, warn, info, debug or trace, got '{}'",
+                    self.logs.level
+                ),
+            ));
+        }
+
+        check_range(path, "performance.nice", self.performance.nice, 1, 19)?;
+        if let Some(percent) = self.performance.cpu_quota_percent {
+            check_range(path, "performance.cpu_quota_percent", percent, 1, 6400)?;
+        }
+        if let Some(megabytes) = self.performance.memory_max_mb {
+            check_range(
+                path,
+                "performance.memory_max_mb",
+                megabytes,
+                128,
+                1024 * 1024,
+            )?;
+        }
+        Ok(())
+    }
+
+    pub fn parse(raw: &str, path: &Path) -> Result<Self, ConfigError> {
+        let config: Self = toml::from_str(raw).map_err(|err| ConfigError::Parse {
+            path: path.to_path_buf(),
+            message: err.to_string(),
+        })?;
+        config.validate(path)?;
+        Ok(config)
+    }
+}
+
+// Lives in the base data dir (LOCUS_DATA_DIR or the platform default), never in a
+// relocated one, so moving the data dir cannot hide the setting that moved it.
+pub fn config_path() -> PathBuf {
+    resolve_base_data_dir().join(CONFIG_FILE_NAME)
+}
+
+// A missing file is simply the defaults.
+fn load(path: &Path) -> Result<ShellConfig, ConfigError> {
+    match fs::read_to_string(path) {
+        Ok(raw) => ShellConfig::parse(&raw, path),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(ShellConfig::default()),
+        Err(source) => Err(ConfigError::Read {
+            path: path.to_path_buf(),
+            source,
+        }),
+    }
+}
+
+static CONFIG: OnceLock<RwLock<Arc<ShellConfig>>> = OnceLock::new();
+
+// Runs before anything reads a setting. A broken file stops the launch instead of
+// falling back to defaults: its paths.data_dir may name a different data dir.
+pub fn init() -> Result<(), ConfigError> {
+    let config = load(&config_path())?;
+    let _ = CONFIG.set(RwLock::new(Arc::new(config)));
+    Ok(())
+}
+
+fn config_cell() -> &'static RwLock<Arc<ShellConfig>> {
+    CONFIG.get_or_init(|| {
+        let config = load(&config_path()).unwrap_or_else(|err| {
+            warn!(error = %err, "using default settings");
+            ShellConfig::default()
+        });
+        RwLock::new(Arc::new(config))
+    })
+}
+
+pub fn shell_config() -> Arc<ShellConfig> {
+    match config_cell().read() {
+        Ok(guard) => Arc::clone(&guard),
+        Err(poisoned) => Arc::clone(&poisoned.into_inner()),
+    }
+}
+
+// An invalid file leaves the running settings untouched. Backend settings apply on the
+// next (re)start of the sidecar; paths.data_dir only on the next launch.
+pub fn reload_shell_config() -> Result<Arc<ShellConfig>, ConfigError> {
+    let config = Arc::new(load(&config_path())?);
+    if let Ok(mut guard) = config_cell().write() {
+        *guard = Arc::clone(&config);
+    }
+    crate::logging::apply_level(&config.logs.level);
+    info!(path = %config_path().display(), "reloaded settings");
+    Ok(config)
+}
+
+#[tauri::command]
+pub fn get_shell_config() -> ShellConfig {
+    shell_config().as_ref().clone()
+}
+
+#[tauri::command]
+pub fn reload_shell_config_command() -> Result<ShellConfig, String> {
+    reload_shell_config()
+        .map(|config| config.as_ref().clone())
+        .map_err(|err| err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(raw: &str) -> Result<ShellConfig, ConfigError> {
+        ShellConfig::parse(raw, Path::new("/data/locus.toml"))
+    }
+
+    #[test]
+    fn empty_file_uses_defaults() {
+        assert_eq!(parse("").expect("empty config"), ShellConfig::default());
+    }
+
+    #[test]
+    fn reads_overrides() {
+        let config = parse(
+            r#"
+            [backend]
+            port = 8123
+            startup_timeout_secs = 90
+
+            [backend.env]
+            LOCUS_LOG_LEVEL = "debug"
+
+            [tray]
+            close_to_tray = false
+            "#,
+        )
+        .expect("valid config");
+
+        assert_eq!(config.backend.port, 8123);
+        assert_eq!(config.backend.startup_timeout_secs, 90);
+        assert_eq!(config.backend.poll_interval_ms, BACKEND_POLL_INTERVAL_MS);
+        assert_eq!(
+            config
+                .backend
+                .env
+                .get("LOCUS_LOG_LEVEL")
+                .map(String::as_str),
+            Some("debug")
+        );
+        assert!(!config.tray.close_to_tray);
+    }
+
+    #[test]
+    fn rejects_invalid_values_with_the_offending_key() {
+        let err = parse("[backend]\nport = 80\n").expect_err("privileged port");
+        assert_eq!(
+            err.to_string(),
+            "'/data/locus.toml': backend.port must be between 1024 and 65535, got 80"
+        );
+
+        let err = parse("[backend.env]\nLOCUS_API_TOKEN = \"x\"\n").expect_err("reserved env");
+        assert!(err
+            .to_string()
+            .contains("cannot override 'LOCUS_API_TOKEN'"));
+
+        let err =
+            parse("[backend.env]\nLOCUS_RELOAD_SUPERVISOR_PID = \"1\"\n").expect_err("reload pid");
+        assert!(err
+            .to_string()
+            .contains("cannot override 'LOCUS_RELOAD_SUPERVISOR_PID'"));
+
+        let err = parse("[paths]\ndata_dir = \"relative\"\n").expect_err("relative dir");
+        assert!(err
+            .to_string()
+            .contains("paths.data_dir must be an absolute path"));
+
+        let err = parse("[tray]\nclose_to_tary = true\n").expect_err("typo");
+        assert!(matches!(err, ConfigError::Parse { .. }));
+        assert!(err.to_string().contains("close_to_tary"));
+    }
+}
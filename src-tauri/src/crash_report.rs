@@ -0,0 +1,306 @@
+use std::backtrace::Backtrace;
+use std::fs;
+use std::io;
+use std::panic;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::Ordering;
+use std::sync::OnceLock;
+use std::thread;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use tauri::api::dialog::blocking::MessageDialogBuilder;
+use tauri::api::dialog::{MessageDialogButtons, MessageDialogKind};
+use tauri::{AppHandle, Manager};
+use tracing::warn;
+
+use crate::health::{self, HealthState};
+use crate::{compat, data_dir_permissions, profiles, resolve_locus_data_dir, BackendState};
+
+const CRASH_DIR_NAME: &str = "crash";
+const REPORT_PREFIX: &str = "crash-";
+const REPORT_SUFFIX: &str = ".txt";
+const SEEN_REPORT_SUFFIX: &str = ".seen.txt";
+const REPORT_RETENTION: usize = 10;
+
+// Set once the app exists; panics before that report without backend details.
+static APP: OnceLock<AppHandle> = OnceLock::new();
+
+struct BackendSnapshot {
+    port: String,
+    process: String,
+    health: String,
+}
+
+pub fn attach_app(app: AppHandle) {
+    let _ = APP.set(app);
+}
+
+// Only try_lock: the panicking thread may be the one holding any of these locks.
+fn backend_snapshot() -> BackendSnapshot {
+    let unknown = || "unknown".to_string();
+    let Some(app) = APP.get() else {
+        return BackendSnapshot {
+            port: unknown(),
+            process: "app not started yet".to_string(),
+            health: unknown(),
+        };
+    };
+
+    let (port, process) = match app.try_state::<BackendState>() {
+        Some(backend) => {
+            let process = match backend.child.try_lock() {
+                Ok(guard) => match guard.as_ref() {
+                    Some(child) => format!("pid {}", child.id()),
+                    None => "not owned by this launch".to_string(),
+                },
+                Err(_) => "unknown (state locked)".to_string(),
+            };
+            let process = if backend.shutting_down.load(Ordering::SeqCst) {
+                format!("{}, shutting down", process)
+            } else {
+                process
+            };
+            (backend.port.load(Ordering::SeqCst).to_string(), process)
+        }
+        None => (unknown(), unknown()),
+    };
+    let health = app
+        .try_state::<HealthState>()
+        .and_then(|state| health::last_health_label(&state))
+        .unwrap_or_else(unknown);
+
+    BackendSnapshot {
+        port,
+        process,
+        health,
+    }
+}
+
+fn render_report(
+    timestamp_secs: u64,
+    thread: &str,
+    message: &str,
+    location: &str,
+    backend: &BackendSnapshot,
+    backtrace: &str,
+) -> String {
+    format!(
+        "Locus shell crash report\n\n\
+         time: {} (unix)\n\
+         shell version: {}\n\
+         os: {} {}\n\
+         profile: {}\n\
+         thread: {}\n\
+         panic: {}\n\
+         location: {}\n\
+         backend port: {}\n\
+         backend process: {}\n\
+         backend status: {}\n\n\
+         backtrace:\n{}\n",
+        timestamp_secs,
+        compat::SHELL_VERSION,
+        std::env::consts::OS,
+        std::env::consts::ARCH,
+        profiles::active_profile().unwrap_or_else(|| profiles::DEFAULT_PROFILE.to_string()),
+        thread,
+        message,
+        location,
+        backend.port,
+        backend.process,
+        backend.health,
+        backtrace
+    )
+}
+
+fn is_report(name: &str) -> bool {
+    name.starts_with(REPORT_PREFIX) && name.ends_with(REPORT_SUFFIX)
+}
+
+fn report_names(crash_dir: &Path) -> Vec<String> {
+    let mut names: Vec<String> = fs::read_dir(crash_dir)
+        .map(|entries| {
+            entries
+                .filter_map(Result::ok)
+                .filter_map(|entry| entry.file_name().into_string().ok())
+                .filter(|name| is_report(name))
+                .collect()
+        })
+        .unwrap_or_default();
+    // Millisecond timestamps sort chronologically as text.
+    names.sort();
+    names
+}
+
+fn prune_reports(crash_dir: &Path) {
+    let names = report_names(crash_dir);
+    let excess = names.len().saturating_sub(REPORT_RETENTION);
+    for name in &names[..excess] {
+        let _ = fs::remove_file(crash_dir.join(name));
+    }
+}
+
+fn write_crash_report(message: &str, location: &str) -> io::Result<PathBuf> {
+    let crash_dir = resolve_locus_data_dir().join(CRASH_DIR_NAME);
+    data_dir_permissions::create_private_dir(&crash_dir)?;
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    let current = thread::current();
+    let report = render_report(
+        now.as_secs(),
+        current.name().unwrap_or("unnamed"),
+        message,
+        location,
+        &backend_snapshot(),
+        &Backtrace::force_capture().to_string(),
+    );
+
+    let path = crash_dir.join(format!(
+        "{}{}{}",
+        REPORT_PREFIX,
+        now.as_millis(),
+        REPORT_SUFFIX
+    ));
+    fs::write(&path, report)?;
+    prune_reports(&crash_dir);
+    Ok(path)
+}
+
+// Release builds have no console on Windows, so without this a panic leaves no trace.
+pub fn install_panic_hook() {
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        let payload = info.payload();
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(|message| message.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "non-string panic payload".to_string());
+        let location = info
+            .location()
+            .map(|location| {
+                format!(
+                    "{}:{}:{}",
+                    location.file(),
+                    location.line(),
+                    location.column()
+                )
+            })
+            .unwrap_or_else(|| "unknown".to_string());
+
+        // Plain stderr: the panic may have happened inside the logger with its lock held.
+        match write_crash_report(&message, &location) {
+            Ok(path) => eprintln!("[tauri] crash report written to {}", path.display()),
+            Err(err) => eprintln!("[tauri] failed to write crash report: {}", err),
+        }
+        default_hook(info);
+    }));
+}
+
+// Marks every unseen report as seen, so each crash is offered exactly once, and returns
+// the newest of them.
+fn take_unseen_report(crash_dir: &Path) -> Option<PathBuf> {
+    let mut newest = None;
+    for name in report_names(crash_dir) {
+        if name.ends_with(SEEN_REPORT_SUFFIX) {
+            continue;
+        }
+        let seen = crash_dir.join(format!(
+            "{}{}",
+            name.trim_end_matches(REPORT_SUFFIX),
+            SEEN_REPORT_SUFFIX
+        ));
+        if fs::rename(crash_dir.join(&name), &seen).is_ok() {
+            newest = Some(seen);
+        }
+    }
+    newest
+}
+
+pub fn offer_previous_crash_report() {
+    let crash_dir = resolve_locus_data_dir().join(CRASH_DIR_NAME);
+    thread::spawn(move || {
+        let Some(report) = take_unseen_report(&crash_dir) else {
+            return;
+        };
+        let show = MessageDialogBuilder::new(
+            "Locus closed unexpectedly",
+            format!(
+                "Locus crashed the last time it ran. A crash report was saved to\n{}",
+                report.display()
+            ),
+        )
+        .kind(MessageDialogKind::Warning)
+        .buttons(MessageDialogButtons::OkCancelWithLabels(
+            String::from("Show report"),
+            String::from("Dismiss"),
+        ))
+        .show();
+        if show {
+            if let Err(err) = crate::startup_dialog::open_path(&report) {
+                warn!(
+                    report = %report.display(),
+                    error = %err,
+                    "failed to open crash report"
+                );
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::scratch_dir;
+
+    #[test]
+    fn report_includes_panic_and_backend_details() {
+        let backend = BackendSnapshot {
+            port: "8003".to_string(),
+            process: "pid 4242".to_string(),
+            health: "Backend: healthy".to_string(),
+        };
+        let report = render_report(
+            1_700_000_000,
+            "main",
+            "called `Option::unwrap()` on a `None` value",
+            "src/main.rs:10:5",
+            &backend,
+            "   0: locus_tauri::main",
+        );
+
+        assert!(report.contains(&format!("shell version: {}", compat::SHELL_VERSION)));
+        assert!(report.contains("panic: called `Option::unwrap()` on a `None` value"));
+        assert!(report.contains("location: src/main.rs:10:5"));
+        assert!(report.contains("backend port: 8003"));
+        assert!(report.contains("backend process: pid 4242"));
+        assert!(report.ends_with("backtrace:\n   0: locus_tauri::main\n"));
+    }
+
+    #[test]
+    fn offers_each_crash_once_and_keeps_a_bounded_history() {
+        let dir = scratch_dir("crash-report");
+        for millis in 0..REPORT_RETENTION + 2 {
+            fs::write(
+                dir.join(format!(
+                    "{}17000000000{:02}{}",
+                    REPORT_PREFIX, millis, REPORT_SUFFIX
+                )),
+                "report",
+            )
+            .expect("write failed");
+        }
+        prune_reports(&dir);
+        assert_eq!(report_names(&dir).len(), REPORT_RETENTION);
+
+        let newest = take_unseen_report(&dir).expect("unseen report");
+        assert_eq!(
+            newest.file_name().and_then(|name| name.to_str()),
+            Some("crash-1700000000011.seen.txt")
+        );
+        assert_eq!(take_unseen_report(&dir), None);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
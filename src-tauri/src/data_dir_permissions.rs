@@ -0,0 +1,195 @@
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use tracing::{info, warn};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PermissionProblem {
+    ForeignOwner { owner: u32, current: u32 },
+    OthersCanAccess { mode: u32 },
+    GroupCanAccess { mode: u32 },
+}
+
+impl PermissionProblem {
+    // Group access is common on shared-group setups, so it only gets a warning.
+    pub fn is_fatal(&self) -> bool {
+        !matches!(self, Self::GroupCanAccess { .. })
+    }
+
+    // Changing the owner needs root; everything else is a chmod away.
+    pub fn is_repairable(&self) -> bool {
+        !matches!(self, Self::ForeignOwner { .. })
+    }
+}
+
+impl fmt::Display for PermissionProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ForeignOwner { owner, current } => write!(
+                f,
+                "the data directory is owned by uid {} but Locus runs as uid {}",
+                owner, current
+            ),
+            Self::OthersCanAccess { mode } => write!(
+                f,
+                "the data directory is accessible to other users (mode {:03o})",
+                mode
+            ),
+            Self::GroupCanAccess { mode } => write!(
+                f,
+                "the data directory is accessible to its group (mode {:03o})",
+                mode
+            ),
+        }
+    }
+}
+
+#[cfg(unix)]
+fn classify(mode: u32, owner: u32, current: u32) -> Option<PermissionProblem> {
+    let mode = mode & 0o777;
+    if owner != current {
+        Some(PermissionProblem::ForeignOwner { owner, current })
+    } else if mode & 0o007 != 0 {
+        Some(PermissionProblem::OthersCanAccess { mode })
+    } else if mode & 0o070 != 0 {
+        Some(PermissionProblem::GroupCanAccess { mode })
+    } else {
+        None
+    }
+}
+
+// Only the directories this creates get 0700; an existing one keeps its mode so
+// `inspect` can still report it.
+#[cfg(unix)]
+pub fn create_private_dir(dir: &Path) -> io::Result<()> {
+    use std::os::unix::fs::DirBuilderExt;
+
+    fs::DirBuilder::new()
+        .recursive(true)
+        .mode(0o700)
+        .create(dir)
+}
+
+#[cfg(not(unix))]
+pub fn create_private_dir(dir: &Path) -> io::Result<()> {
+    fs::create_dir_all(dir)
+}
+
+// A directory that does not exist yet is fine: it will be created private.
+#[cfg(unix)]
+pub fn inspect(dir: &Path) -> Option<PermissionProblem> {
+    use std::os::unix::fs::MetadataExt;
+
+    let metadata = match fs::metadata(dir) {
+        Ok(metadata) => metadata,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return None,
+        Err(err) => {
+            warn!(dir = %dir.display(), error = %err, "could not inspect data dir");
+            return None;
+        }
+    };
+    // SAFETY: geteuid has no preconditions and cannot fail.
+    let current = unsafe { libc::geteuid() };
+    classify(metadata.mode(), metadata.uid(), current)
+}
+
+// Windows profile directories already carry per-user ACLs.
+#[cfg(not(unix))]
+pub fn inspect(_dir: &Path) -> Option<PermissionProblem> {
+    None
+}
+
+// inspect and repair follow a symlinked data dir, so they act on the folder it points to,
+// which may be a shared mount.
+pub fn link_target(dir: &Path) -> Option<PathBuf> {
+    let metadata = fs::symlink_metadata(dir).ok()?;
+    if !metadata.file_type().is_symlink() {
+        return None;
+    }
+    fs::canonicalize(dir).ok()
+}
+
+// The directory mode alone locks others out of everything below it.
+#[cfg(unix)]
+pub fn repair(dir: &Path) -> io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    fs::set_permissions(dir, fs::Permissions::from_mode(0o700))?;
+    match link_target(dir) {
+        Some(target) => info!(
+            dir = %dir.display(),
+            target = %target.display(),
+            "restricted the data dir's link target to its owner"
+        ),
+        None => info!(dir = %dir.display(), "restricted data dir to its owner"),
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+pub fn repair(_dir: &Path) -> io::Result<()> {
+    Ok(())
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::test_support::scratch_dir;
+    use std::os::unix::fs::PermissionsExt;
+
+    #[test]
+    fn classifies_owner_and_mode() {
+        assert_eq!(classify(0o40700, 1000, 1000), None);
+        assert_eq!(
+            classify(0o40750, 1000, 1000),
+            Some(PermissionProblem::GroupCanAccess { mode: 0o750 })
+        );
+        assert_eq!(
+            classify(0o40755, 1000, 1000),
+            Some(PermissionProblem::OthersCanAccess { mode: 0o755 })
+        );
+        assert_eq!(
+            classify(0o40700, 0, 1000),
+            Some(PermissionProblem::ForeignOwner {
+                owner: 0,
+                current: 1000
+            })
+        );
+    }
+
+    #[test]
+    fn creates_private_and_repairs_open_dirs() {
+        let dir = scratch_dir("data-dir-permissions");
+        create_private_dir(&dir.join("nested")).expect("create failed");
+        assert_eq!(inspect(&dir.join("nested")), None);
+
+        fs::set_permissions(&dir, fs::Permissions::from_mode(0o755)).expect("chmod failed");
+        assert_eq!(
+            inspect(&dir),
+            Some(PermissionProblem::OthersCanAccess { mode: 0o755 })
+        );
+        repair(&dir).expect("repair failed");
+        assert_eq!(inspect(&dir), None);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn reports_the_folder_behind_a_linked_data_dir() {
+        let dir = scratch_dir("data-dir-link");
+        let shared = dir.join("shared");
+        let link = dir.join("locus");
+        fs::create_dir_all(&shared).expect("mkdir failed");
+        std::os::unix::fs::symlink(&shared, &link).expect("symlink failed");
+
+        assert_eq!(link_target(&shared), None);
+        assert_eq!(
+            link_target(&link),
+            Some(fs::canonicalize(&shared).expect("canonicalize failed"))
+        );
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,154 @@
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::thread;
+use std::time::Duration;
+
+use tauri::{AppHandle, Manager, State};
+use tracing::{debug, error, info};
+
+use crate::health::fetch_backend_health;
+use crate::{
+    api_token, config, create_main_window, data_dir_permissions, install_backend_child,
+    process_group, resolve_locus_data_dir, wait_for_backend_ready_or_exit, BackendState,
+};
+
+const DEV_SPAWN_BACKEND_ENV: &str = "LOCUS_DEV_SPAWN_BACKEND";
+const DEV_PYTHON_ENV: &str = "LOCUS_DEV_PYTHON";
+
+pub fn spawn_requested() -> bool {
+    matches!(
+        std::env::var(DEV_SPAWN_BACKEND_ENV)
+            .as_deref()
+            .map(str::trim),
+        Ok("1") | Ok("true")
+    )
+}
+
+// Debug builds always run from a checkout, so the sources sit next to the manifest.
+fn repo_root() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .map(Path::to_path_buf)
+        .unwrap_or_else(|| PathBuf::from(".."))
+}
+
+// Same venv the README sets up, unless LOCUS_DEV_PYTHON points elsewhere.
+fn resolve_python(repo_root: &Path) -> PathBuf {
+    if let Some(python) = std::env::var_os(DEV_PYTHON_ENV).filter(|value| !value.is_empty()) {
+        return PathBuf::from(python);
+    }
+
+    #[cfg(target_os = "windows")]
+    let (venv_python, system_python) = (repo_root.join(".venv/Scripts/python.exe"), "python");
+    #[cfg(not(target_os = "windows"))]
+    let (venv_python, system_python) = (repo_root.join(".venv/bin/python"), "python3");
+
+    if venv_python.is_file() {
+        venv_python
+    } else {
+        PathBuf::from(system_python)
+    }
+}
+
+// Straight to the terminal, not through tracing: uvicorn's access log would otherwise
+// fill shell.log and push the shell's own records out of the diagnostics buffer.
+fn forward_output<R: Read + Send + 'static>(stream: Option<R>, to_stderr: bool) {
+    let Some(stream) = stream else {
+        return;
+    };
+    thread::spawn(move || {
+        let mut reader = BufReader::new(stream);
+        let mut line = Vec::new();
+        loop {
+            line.clear();
+            match reader.read_until(b'\n', &mut line) {
+                Ok(0) | Err(_) => break,
+                Ok(_) => {
+                    let text = String::from_utf8_lossy(&line);
+                    let text = text.trim_end_matches(['\r', '\n']);
+                    let _ = if to_stderr {
+                        writeln!(io::stderr().lock(), "[backend] {}", text)
+                    } else {
+                        writeln!(io::stdout().lock(), "[backend] {}", text)
+                    };
+                }
+            }
+        }
+    });
+}
+
+fn spawn_dev_backend(port: u16) -> io::Result<Child> {
+    let repo_root = repo_root();
+    let backend_dir = repo_root.join("backend");
+    let data_dir = resolve_locus_data_dir();
+    let _ = data_dir_permissions::create_private_dir(&data_dir);
+
+    let mut command = Command::new(resolve_python(&repo_root));
+    command
+        .envs(&config::shell_config().backend.env)
+        .arg(backend_dir.join("service_entry.py"))
+        .arg("--port")
+        .arg(port.to_string())
+        .arg("--reload")
+        .current_dir(&backend_dir)
+        .env("LOCUS_DATA_DIR", data_dir)
+        .env("LOCUS_PARENT_PID", std::process::id().to_string())
+        .env(api_token::API_TOKEN_ENV, api_token::api_token())
+        .env("PYTHONUNBUFFERED", "1")
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    // The reloader runs the app in a worker process; the group covers both.
+    process_group::isolate(&mut command);
+
+    let mut child = command.spawn()?;
+    forward_output(child.stdout.take(), false);
+    forward_output(child.stderr.take(), true);
+    Ok(child)
+}
+
+fn run_dev_backend(app: &AppHandle) -> Result<(), String> {
+    let settings = config::shell_config();
+    let mut port = settings.backend.port;
+    if fetch_backend_health(port).is_some() {
+        info!(
+            port,
+            "dev mode: backend already running, not spawning another"
+        );
+        return Ok(());
+    }
+
+    let mut child = spawn_dev_backend(port)
+        .map_err(|err| format!("failed to spawn backend/service_entry.py: {}", err))?;
+    let on_phase = |phase| debug!(?phase, "dev mode: backend startup");
+    if let Err(err) = wait_for_backend_ready_or_exit(
+        &mut child,
+        &mut port,
+        false,
+        Duration::from_secs(settings.backend.startup_timeout_secs),
+        &on_phase,
+    ) {
+        process_group::terminate(&mut child);
+        let _ = child.wait();
+        return Err(err.to_string());
+    }
+
+    // Owned like a release sidecar, so quitting the app stops it too.
+    let state: State<BackendState> = app.state();
+    state.port.store(port, std::sync::atomic::Ordering::SeqCst);
+    install_backend_child(&state, child);
+    Ok(())
+}
+
+// The main window still opens on failure; the UI keeps retrying a backend started by hand.
+pub fn start_dev_backend(app: AppHandle) {
+    thread::spawn(move || {
+        if let Err(err) = run_dev_backend(&app) {
+            error!(error = %err, "dev mode: backend did not start");
+        }
+        if let Err(err) = create_main_window(&app) {
+            error!(error = %err, "failed to create main window");
+        }
+    });
+}
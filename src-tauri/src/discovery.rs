@@ -0,0 +1,112 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use crate::compat::BackendVersion;
+use crate::health::fetch_backend_health;
+
+const DISCOVERY_FILE_NAME: &str = "backend.json";
+
+// Written by the backend itself on startup, see backend/app/discovery.py.
+#[derive(Debug, Deserialize)]
+pub struct DiscoveryRecord {
+    pub pid: u32,
+    pub port: u16,
+    data_dir: PathBuf,
+    // Filled in from the health check; the file's own version field is informational.
+    #[serde(skip)]
+    pub backend_version: Option<BackendVersion>,
+}
+
+fn same_directory(left: &Path, right: &Path) -> bool {
+    match (fs::canonicalize(left), fs::canonicalize(right)) {
+        (Ok(left), Ok(right)) => left == right,
+        _ => left == right,
+    }
+}
+
+fn read_discovery_record(data_dir: &Path) -> Option<DiscoveryRecord> {
+    let raw = fs::read(data_dir.join(DISCOVERY_FILE_NAME)).ok()?;
+    let record: DiscoveryRecord = serde_json::from_slice(&raw).ok()?;
+    if !same_directory(&record.data_dir, data_dir) {
+        return None;
+    }
+    Some(record)
+}
+
+// Used while a freshly spawned backend starts: the record is trusted only if our child wrote it.
+pub fn advertised_port(data_dir: &Path, pid: u32) -> Option<u16> {
+    read_discovery_record(data_dir)
+        .filter(|record| record.pid == pid)
+        .map(|record| record.port)
+}
+
+#[cfg(target_os = "linux")]
+fn record_process_alive(record: &DiscoveryRecord) -> bool {
+    crate::pid_exists(record.pid)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn record_process_alive(_record: &DiscoveryRecord) -> bool {
+    true
+}
+
+// A stale file (backend killed without cleanup) fails either the pid or the health check,
+// and a sidecar owned by another shell launch rejects our token with a 401.
+pub fn find_running_backend(data_dir: &Path) -> Option<DiscoveryRecord> {
+    let mut record = read_discovery_record(data_dir)?;
+    if !record_process_alive(&record) {
+        return None;
+    }
+    record.backend_version = fetch_backend_health(record.port)?.version;
+    Some(record)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::scratch_dir;
+    use std::net::TcpListener;
+
+    fn write_record(dir: &Path, data_dir: &Path, port: u16) {
+        let body = serde_json::json!({
+            "pid": std::process::id(),
+            "host": "127.0.0.1",
+            "port": port,
+            "data_dir": data_dir,
+            "version": "1.5.0",
+        });
+        fs::write(dir.join(DISCOVERY_FILE_NAME), body.to_string()).expect("write failed");
+    }
+
+    #[test]
+    fn ignores_records_for_another_data_dir() {
+        let dir = scratch_dir("discovery-other");
+        write_record(&dir, &dir.join("elsewhere"), 8000);
+
+        assert!(read_discovery_record(&dir).is_none());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn rejects_record_when_backend_is_not_listening() {
+        let dir = scratch_dir("discovery-stale");
+        let listener = TcpListener::bind(("127.0.0.1", 0)).expect("ephemeral bind failed");
+        let port = listener
+            .local_addr()
+            .expect("failed to read local addr")
+            .port();
+        drop(listener);
+        write_record(&dir, &dir, port);
+
+        let record = read_discovery_record(&dir).expect("record should parse");
+        assert_eq!(record.port, port);
+        assert!(find_running_backend(&dir).is_none());
+        assert_eq!(advertised_port(&dir, std::process::id()), Some(port));
+        assert_eq!(advertised_port(&dir, std::process::id() + 1), None);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,275 @@
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::path::Path;
+use std::sync::atomic::Ordering;
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+
+use serde::Serialize;
+use serde_json::Value;
+use tauri::{AppHandle, Manager, State};
+use tracing::info;
+
+use crate::api_token::{api_token, API_TOKEN_HEADER};
+use crate::compat::BackendVersion;
+use crate::{backend_socket, BackendState};
+
+pub const BACKEND_HEALTH_EVENT: &str = "locus://backend-health";
+pub const TRAY_HEALTH_ITEM_ID: &str = "backend_health";
+
+const HEALTH_REQUEST_TIMEOUT_MS: u64 = 500;
+const HEALTH_RESPONSE_MAX_BYTES: u64 = 64 * 1024;
+const HEALTH_MONITOR_INTERVAL_MS: u64 = 5000;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ComponentStatus {
+    Ready,
+    Starting,
+    Stopped,
+    Error,
+    Unknown,
+}
+
+impl ComponentStatus {
+    fn parse(raw: Option<&str>) -> Self {
+        match raw {
+            Some("ready") => Self::Ready,
+            Some("starting") => Self::Starting,
+            Some("stopped") => Self::Stopped,
+            Some("error") => Self::Error,
+            _ => Self::Unknown,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct BackendHealth {
+    pub db: ComponentStatus,
+    pub watcher: ComponentStatus,
+    pub snapshot_engine: ComponentStatus,
+    pub version: Option<BackendVersion>,
+}
+
+impl BackendHealth {
+    // Backends from before `components` existed only report `db` and `background_service`.
+    fn from_legacy(body: &Value) -> Self {
+        let db = match body.get("db").and_then(Value::as_str) {
+            Some("connected") => ComponentStatus::Ready,
+            Some(_) => ComponentStatus::Error,
+            None => ComponentStatus::Unknown,
+        };
+        let watcher = match body.get("background_service").and_then(Value::as_str) {
+            Some("active") => ComponentStatus::Ready,
+            _ => ComponentStatus::Unknown,
+        };
+
+        Self {
+            db,
+            watcher,
+            snapshot_engine: ComponentStatus::Unknown,
+            version: None,
+        }
+    }
+
+    // Unknown means an older backend that does not report the component, not a fault.
+    pub fn degraded_components(&self) -> Vec<&'static str> {
+        [
+            ("db", self.db),
+            ("watcher", self.watcher),
+            ("snapshot_engine", self.snapshot_engine),
+        ]
+        .into_iter()
+        .filter(|(_, status)| !matches!(status, ComponentStatus::Ready | ComponentStatus::Unknown))
+        .map(|(name, _)| name)
+        .collect()
+    }
+
+    pub fn parse(body: &[u8]) -> Option<Self> {
+        let body: Value = serde_json::from_slice(body).ok()?;
+        let components = match body.get("components") {
+            Some(components) => components,
+            None => return Some(Self::from_legacy(&body)),
+        };
+        let field =
+            |name: &str| ComponentStatus::parse(components.get(name).and_then(Value::as_str));
+
+        Some(Self {
+            db: field("db"),
+            watcher: field("watcher"),
+            snapshot_engine: field("snapshot_engine"),
+            version: BackendVersion::parse(&body),
+        })
+    }
+}
+
+fn split_http_response(raw: &[u8]) -> Option<(u16, &[u8])> {
+    let header_end = raw.windows(4).position(|window| window == b"\r\n\r\n")?;
+    let head = String::from_utf8_lossy(&raw[..header_end]);
+    let status_line = head.lines().next()?;
+    let mut parts = status_line.split_whitespace();
+    let version = parts.next()?;
+    if !version.starts_with("HTTP/1.") {
+        return None;
+    }
+    let status = parts.next()?.parse::<u16>().ok()?;
+    Some((status, &raw[header_end + 4..]))
+}
+
+fn fetch_backend_health_over_socket(socket_path: &Path) -> Option<BackendHealth> {
+    let timeout = Duration::from_millis(HEALTH_REQUEST_TIMEOUT_MS);
+    let response =
+        backend_socket::send_request(socket_path, "GET", "/health", &[], &[], timeout).ok()?;
+    match response.status {
+        200 => BackendHealth::parse(&response.body),
+        _ => None,
+    }
+}
+
+// In socket mode the port is meaningless; the backend only listens on the socket.
+pub fn fetch_backend_health(port: u16) -> Option<BackendHealth> {
+    if let Some(socket_path) = backend_socket::unix_socket_path() {
+        return fetch_backend_health_over_socket(socket_path);
+    }
+
+    let addr = format!("127.0.0.1:{}", port);
+    let timeout = Duration::from_millis(HEALTH_REQUEST_TIMEOUT_MS);
+    let mut stream = TcpStream::connect_timeout(&addr.parse().ok()?, timeout).ok()?;
+
+    let _ = stream.set_read_timeout(Some(timeout));
+    let _ = stream.set_write_timeout(Some(timeout));
+
+    let request = format!(
+        "GET /health HTTP/1.1\r\nHost: 127.0.0.1\r\n{}: {}\r\nConnection: close\r\n\r\n",
+        API_TOKEN_HEADER,
+        api_token()
+    );
+    stream.write_all(request.as_bytes()).ok()?;
+
+    let mut response = Vec::new();
+    stream
+        .take(HEALTH_RESPONSE_MAX_BYTES)
+        .read_to_end(&mut response)
+        .ok()?;
+
+    match split_http_response(&response)? {
+        (200, body) => BackendHealth::parse(body),
+        _ => None,
+    }
+}
+
+// None while the backend is unreachable (or before the first successful poll).
+#[derive(Default)]
+pub struct HealthState {
+    current: Mutex<Option<BackendHealth>>,
+}
+
+#[tauri::command]
+pub fn get_backend_health(state: State<HealthState>) -> Option<BackendHealth> {
+    state
+        .current
+        .lock()
+        .ok()
+        .and_then(|current| current.clone())
+}
+
+// Never blocks: the crash report asks from a panic hook that may hold the lock itself.
+pub fn last_health_label(state: &HealthState) -> Option<String> {
+    state
+        .current
+        .try_lock()
+        .ok()
+        .map(|current| tray_health_label(current.as_ref()))
+}
+
+fn tray_health_label(health: Option<&BackendHealth>) -> String {
+    let Some(health) = health else {
+        return "Backend: unreachable".to_string();
+    };
+    let degraded = health.degraded_components();
+    if degraded.is_empty() {
+        "Backend: healthy".to_string()
+    } else {
+        format!("Backend: degraded ({})", degraded.join(", "))
+    }
+}
+
+fn monitor_backend_health(app: AppHandle) {
+    let backend: State<BackendState> = app.state();
+    let health_state: State<HealthState> = app.state();
+
+    loop {
+        if backend.shutting_down.load(Ordering::SeqCst) {
+            return;
+        }
+
+        let latest = fetch_backend_health(backend.port.load(Ordering::SeqCst));
+        let changed = match health_state.current.lock() {
+            Ok(mut current) if *current != latest => {
+                *current = latest.clone();
+                true
+            }
+            _ => false,
+        };
+
+        if changed {
+            let label = tray_health_label(latest.as_ref());
+            info!(status = %label, "backend health changed");
+            let _ = app
+                .tray_handle()
+                .get_item(TRAY_HEALTH_ITEM_ID)
+                .set_title(label);
+            let _ = app.emit_all(BACKEND_HEALTH_EVENT, latest);
+        }
+
+        thread::sleep(Duration::from_millis(HEALTH_MONITOR_INTERVAL_MS));
+    }
+}
+
+pub fn start_health_monitor(app: AppHandle) {
+    thread::spawn(move || monitor_backend_health(app));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_component_status() {
+        let body = br#"{"db":"connected","components":{"db":"ready","watcher":"starting","snapshot_engine":"stopped"}}"#;
+        let health = BackendHealth::parse(body).expect("health should parse");
+
+        assert_eq!(health.db, ComponentStatus::Ready);
+        assert_eq!(health.watcher, ComponentStatus::Starting);
+        assert_eq!(health.snapshot_engine, ComponentStatus::Stopped);
+        assert_eq!(
+            health.degraded_components(),
+            vec!["watcher", "snapshot_engine"]
+        );
+        assert_eq!(
+            tray_health_label(Some(&health)),
+            "Backend: degraded (watcher, snapshot_engine)"
+        );
+        assert_eq!(tray_health_label(None), "Backend: unreachable");
+    }
+
+    #[test]
+    fn falls_back_to_legacy_health_shape() {
+        let body = br#"{"db":"connected","background_service":"active"}"#;
+        let health = BackendHealth::parse(body).expect("health should parse");
+
+        assert_eq!(health.db, ComponentStatus::Ready);
+        assert_eq!(health.watcher, ComponentStatus::Ready);
+        assert_eq!(health.snapshot_engine, ComponentStatus::Unknown);
+        assert!(health.degraded_components().is_empty());
+        assert_eq!(health.version, None);
+    }
+
+    #[test]
+    fn splits_status_and_body() {
+        let raw = b"HTTP/1.1 503 Service Unavailable\r\ncontent-length: 2\r\n\r\n{}";
+        assert_eq!(split_http_response(raw), Some((503, &b"{}"[..])));
+        assert_eq!(split_http_response(b"garbage"), None);
+    }
+}
@@ -0,0 +1,66 @@
+use std::net::TcpListener;
+use std::process::Command;
+
+#[cfg(unix)]
+const LISTEN_FDS_START: i32 = 3;
+
+// systemd socket-activation convention: the listener arrives as fd 3 and LISTEN_FDS
+// says how many there are. LISTEN_PID is left unset because the child pid is unknown
+// until after fork; the backend accepts that and clears the variables once adopted.
+#[cfg(unix)]
+pub fn pass_listener(command: &mut Command, listener: &TcpListener) -> bool {
+    use std::io;
+    use std::os::unix::io::AsRawFd;
+    use std::os::unix::process::CommandExt;
+
+    let fd = listener.as_raw_fd();
+    command
+        .env("LISTEN_FDS", "1")
+        .env("LISTEN_FDNAMES", "locus-api");
+
+    // SAFETY: only async-signal-safe libc calls run between fork and exec.
+    unsafe {
+        command.pre_exec(move || {
+            let result = if fd == LISTEN_FDS_START {
+                // dup2 onto itself is a no-op that keeps FD_CLOEXEC, so clear it directly.
+                libc::fcntl(fd, libc::F_SETFD, 0)
+            } else {
+                libc::dup2(fd, LISTEN_FDS_START)
+            };
+            if result == -1 {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(())
+        });
+    }
+    true
+}
+
+#[cfg(not(unix))]
+pub fn pass_listener(_command: &mut Command, _listener: &TcpListener) -> bool {
+    false
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+    use std::process::Stdio;
+
+    #[test]
+    fn child_sees_listener_on_fd_three() {
+        let listener = TcpListener::bind(("127.0.0.1", 0)).expect("ephemeral bind failed");
+
+        let mut command = Command::new("sh");
+        command
+            .arg("-c")
+            .arg("echo \"$LISTEN_FDS\"; readlink /proc/self/fd/3")
+            .stdout(Stdio::piped());
+        assert!(pass_listener(&mut command, &listener));
+
+        let output = command.output().expect("failed to run sh");
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let mut lines = stdout.lines();
+        assert_eq!(lines.next(), Some("1"));
+        assert!(lines.next().unwrap_or("").starts_with("socket:"));
+    }
+}
@@ -0,0 +1,289 @@
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt;
+use std::path::Path;
+use std::sync::mpsc::{self, SyncSender};
+use std::sync::{Mutex, OnceLock};
+use std::thread;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+use tracing::field::{Field, Visit};
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::layer::{Context, Layer, SubscriberExt};
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{fmt as tracing_fmt, reload, Registry};
+
+use crate::backend_logs::RotatingLogFile;
+use crate::config;
+
+pub const SHELL_LOG_EVENT: &str = "locus://shell-log";
+pub const DEFAULT_LOG_LEVEL: &str = "info";
+
+const SHELL_LOG_FILE_NAME: &str = "shell.log";
+const RECENT_RECORDS: usize = 1000;
+const FORWARD_QUEUE: usize = 1024;
+
+#[derive(Clone, Debug, Serialize)]
+pub struct LogRecord {
+    seq: u64,
+    timestamp_ms: u64,
+    level: &'static str,
+    target: String,
+    message: String,
+    fields: BTreeMap<String, String>,
+    #[serde(skip)]
+    severity: Level,
+}
+
+struct LogSink {
+    next_seq: u64,
+    recent: VecDeque<LogRecord>,
+    file: Option<RotatingLogFile>,
+}
+
+// Records arrive from every thread; nothing in here may log, or it would deadlock.
+static SINK: Mutex<LogSink> = Mutex::new(LogSink {
+    next_seq: 1,
+    recent: VecDeque::new(),
+    file: None,
+});
+static LEVEL: OnceLock<reload::Handle<LevelFilter, Registry>> = OnceLock::new();
+static FORWARDER: OnceLock<SyncSender<LogRecord>> = OnceLock::new();
+
+fn level_name(level: &Level) -> &'static str {
+    match *level {
+        Level::ERROR => "error",
+        Level::WARN => "warn",
+        Level::INFO => "info",
+        Level::DEBUG => "debug",
+        Level::TRACE => "trace",
+    }
+}
+
+pub fn parse_level(raw: &str) -> Option<Level> {
+    match raw.trim().to_ascii_lowercase().as_str() {
+        "error" => Some(Level::ERROR),
+        "warn" => Some(Level::WARN),
+        "info" => Some(Level::INFO),
+        "debug" => Some(Level::DEBUG),
+        "trace" => Some(Level::TRACE),
+        _ => None,
+    }
+}
+
+#[derive(Default)]
+struct RecordVisitor {
+    message: String,
+    fields: BTreeMap<String, String>,
+}
+
+impl Visit for RecordVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message = value.to_string();
+        } else {
+            self.fields
+                .insert(field.name().to_string(), value.to_string());
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            self.message = format!("{:?}", value);
+        } else {
+            self.fields
+                .insert(field.name().to_string(), format!("{:?}", value));
+        }
+    }
+}
+
+fn write_record(file: &mut RotatingLogFile, record: &LogRecord) {
+    if let Ok(mut line) = serde_json::to_vec(record) {
+        line.push(b'\n');
+        let _ = file.write_line(&line);
+    }
+}
+
+// Keeps the recent records for the diagnostics page and mirrors them to shell.log.
+struct ShellLogLayer;
+
+impl<S: Subscriber> Layer<S> for ShellLogLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let mut visitor = RecordVisitor::default();
+        event.record(&mut visitor);
+        let metadata = event.metadata();
+        let mut record = LogRecord {
+            seq: 0,
+            timestamp_ms: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|elapsed| elapsed.as_millis() as u64)
+                .unwrap_or(0),
+            level: level_name(metadata.level()),
+            target: metadata.target().to_string(),
+            message: visitor.message,
+            fields: visitor.fields,
+            severity: *metadata.level(),
+        };
+
+        {
+            let Ok(mut sink) = SINK.lock() else {
+                return;
+            };
+            record.seq = sink.next_seq;
+            sink.next_seq += 1;
+            if let Some(file) = sink.file.as_mut() {
+                write_record(file, &record);
+            }
+            if sink.recent.len() == RECENT_RECORDS {
+                sink.recent.pop_front();
+            }
+            sink.recent.push_back(record.clone());
+        }
+
+        // A webview that cannot keep up loses live records, never the buffered ones.
+        if let Some(forwarder) = FORWARDER.get() {
+            let _ = forwarder.try_send(record);
+        }
+    }
+}
+
+// Console and buffer only until `set_log_dir` knows where the data dir is.
+pub fn init() {
+    let (filter, handle) = reload::Layer::new(LevelFilter::from_level(Level::INFO));
+    let installed = tracing_subscriber::registry()
+        .with(filter)
+        .with(tracing_fmt::layer().with_writer(std::io::stderr))
+        .with(ShellLogLayer)
+        .try_init();
+    if installed.is_ok() {
+        let _ = LEVEL.set(handle);
+    }
+}
+
+pub fn apply_level(raw: &str) {
+    let Some(level) = parse_level(raw) else {
+        tracing::warn!(level = raw, "ignoring unknown log level");
+        return;
+    };
+    if let Some(handle) = LEVEL.get() {
+        if handle.reload(LevelFilter::from_level(level)).is_err() {
+            tracing::warn!(level = raw, "could not change the log level");
+        }
+    }
+}
+
+// Follows the data dir: called at launch and again whenever a relocation or profile
+// switch moves it. The first file also receives what was logged before it existed.
+pub fn set_log_dir(log_dir: &Path) {
+    let settings = config::shell_config();
+    let opened = RotatingLogFile::open(
+        log_dir,
+        SHELL_LOG_FILE_NAME,
+        settings.logs.max_bytes,
+        settings.logs.retention,
+    );
+    let failure = match opened {
+        Ok(mut file) => {
+            if let Ok(mut sink) = SINK.lock() {
+                if sink.file.is_none() {
+                    for record in &sink.recent {
+                        write_record(&mut file, record);
+                    }
+                }
+                sink.file = Some(file);
+            }
+            None
+        }
+        Err(err) => Some(err),
+    };
+    if let Some(err) = failure {
+        tracing::warn!(
+            dir = %log_dir.display(),
+            error = %err,
+            "failed to open shell log"
+        );
+    }
+}
+
+pub fn start_log_forwarder(app: AppHandle) {
+    let (sender, receiver) = mpsc::sync_channel::<LogRecord>(FORWARD_QUEUE);
+    if FORWARDER.set(sender).is_err() {
+        return;
+    }
+    thread::spawn(move || {
+        for record in receiver {
+            let _ = app.emit_all(SHELL_LOG_EVENT, record);
+        }
+    });
+}
+
+fn recent_records(limit: usize, after_seq: u64, min_level: Level) -> Vec<LogRecord> {
+    let Ok(sink) = SINK.lock() else {
+        return Vec::new();
+    };
+    // Level orders verbose above severe, so "at least warn" is `<= WARN`.
+    let matching: Vec<&LogRecord> = sink
+        .recent
+        .iter()
+        .filter(|record| record.seq > after_seq && record.severity <= min_level)
+        .collect();
+    let skip = matching.len().saturating_sub(limit);
+    matching.into_iter().skip(skip).cloned().collect()
+}
+
+// Oldest first. Pass the last seen `seq` as `after_seq` to pick up where a previous call
+// (or the live `locus://shell-log` events) left off.
+#[tauri::command]
+pub fn get_shell_log(
+    limit: Option<usize>,
+    after_seq: Option<u64>,
+    min_level: Option<String>,
+) -> Result<Vec<LogRecord>, String> {
+    let min_level = match min_level {
+        Some(raw) => parse_level(&raw).ok_or_else(|| format!("unknown log level '{}'", raw))?,
+        None => Level::TRACE,
+    };
+    Ok(recent_records(
+        limit.unwrap_or(RECENT_RECORDS).min(RECENT_RECORDS),
+        after_seq.unwrap_or(0),
+        min_level,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn captures_structured_records_with_levels() {
+        let subscriber = tracing_subscriber::registry().with(ShellLogLayer);
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::debug!(detail = "noise", "logging test chatter");
+            tracing::warn!(port = 8123, reason = %"busy", "logging test port warning");
+        });
+
+        let records = recent_records(RECENT_RECORDS, 0, Level::TRACE);
+        let warning = records
+            .iter()
+            .find(|record| record.message == "logging test port warning")
+            .expect("warning captured");
+        assert_eq!(warning.level, "warn");
+        assert_eq!(warning.fields.get("port").map(String::as_str), Some("8123"));
+        assert_eq!(
+            warning.fields.get("reason").map(String::as_str),
+            Some("busy")
+        );
+
+        let warnings_only = recent_records(RECENT_RECORDS, 0, Level::WARN);
+        assert!(warnings_only
+            .iter()
+            .all(|record| record.message != "logging test chatter"));
+        assert!(recent_records(RECENT_RECORDS, warning.seq, Level::TRACE)
+            .iter()
+            .all(|record| record.seq > warning.seq));
+        assert_eq!(parse_level(" Debug "), Some(Level::DEBUG));
+        assert_eq!(parse_level("verbose"), None);
+    }
+}
@@ -1,89 +1,233 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
-use std::io::{Read, Write};
-use std::net::{TcpListener, TcpStream};
+mod api_token;
+mod backend_logs;
+mod backend_socket;
+mod compat;
+mod config;
+mod crash_report;
+mod data_dir_permissions;
+mod dev_backend;
+mod discovery;
+mod health;
+mod listen_fds;
+mod logging;
+mod performance;
+mod process_group;
+mod profiles;
+mod recent_files;
+mod relocate;
+mod resource_usage;
+mod shutdown;
+mod sidecar_integrity;
+mod single_instance;
+mod startup;
+mod startup_dialog;
+mod supervisor;
+#[cfg(test)]
+mod test_support;
+mod tray_controls;
+
+use std::fmt;
+use std::net::TcpListener;
 use std::path::PathBuf;
-use std::process::{Child, Command, Stdio};
-use std::sync::Mutex;
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::sync::atomic::{AtomicBool, AtomicU16, Ordering};
+use std::sync::{Mutex, OnceLock, RwLock};
 use std::thread;
 use std::time::{Duration, Instant};
 
-#[cfg(target_os = "linux")]
-use std::collections::HashSet;
-
 #[cfg(target_os = "windows")]
 use std::os::windows::process::CommandExt;
 
+use tauri::api::dialog::{MessageDialogBuilder, MessageDialogKind};
 use tauri::{
-    CustomMenuItem, Manager, RunEvent, State, SystemTray, SystemTrayEvent, SystemTrayMenu,
-    SystemTrayMenuItem, WindowEvent,
+    AppHandle, CustomMenuItem, Manager, RunEvent, State, SystemTray, SystemTrayEvent,
+    SystemTrayMenu, SystemTrayMenuItem, Window, WindowBuilder, WindowEvent, WindowUrl,
 };
+use tracing::{debug, error, info, warn};
+
+use health::{fetch_backend_health, ComponentStatus, HealthState};
+use startup::{StartupPhase, StartupState};
 
 const DEFAULT_BACKEND_PORT: u16 = 8000;
 const BACKEND_PORT_SEARCH_LIMIT: u16 = 20;
 const BACKEND_STARTUP_TIMEOUT_SECS: u64 = 45;
 const BACKEND_POLL_INTERVAL_MS: u64 = 150;
 
+#[derive(Debug)]
+enum BackendStartupError {
+    ExecutableDirUnavailable(String),
+    BinaryNotFound {
+        path: PathBuf,
+    },
+    SpawnFailed {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    SocketUnavailable {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    ExitedBeforeReady {
+        port: u16,
+        status: ExitStatus,
+    },
+    StatusPollFailed {
+        port: u16,
+        source: std::io::Error,
+    },
+    ReadyTimeout {
+        port: u16,
+        timeout: Duration,
+    },
+    Incompatible(compat::Incompatibility),
+    SidecarIntegrity(sidecar_integrity::IntegrityError),
+}
+
+impl fmt::Display for BackendStartupError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ExecutableDirUnavailable(reason) => write!(
+                f,
+                "failed to resolve the app executable directory: {}",
+                reason
+            ),
+            Self::BinaryNotFound { path } => write!(
+                f,
+                "failed to locate backend executable next to app binary: {}",
+                path.display()
+            ),
+            Self::SpawnFailed { path, source } => write!(
+                f,
+                "failed to spawn backend binary '{}': {}",
+                path.display(),
+                source
+            ),
+            Self::SocketUnavailable { path, source } => write!(
+                f,
+                "failed to prepare backend socket '{}': {}",
+                path.display(),
+                source
+            ),
+            Self::ExitedBeforeReady { port, status } => write!(
+                f,
+                "backend exited before healthcheck on port {} with status {}",
+                port, status
+            ),
+            Self::StatusPollFailed { port, source } => write!(
+                f,
+                "failed while polling backend process status on port {}: {}",
+                port, source
+            ),
+            Self::ReadyTimeout { port, timeout } => write!(
+                f,
+                "backend failed to become ready on port {} within {}s",
+                port,
+                timeout.as_secs()
+            ),
+            Self::Incompatible(reason) => write!(f, "incompatible backend: {}", reason),
+            Self::SidecarIntegrity(reason) => {
+                write!(f, "refusing to start an unverified sidecar: {}", reason)
+            }
+        }
+    }
+}
+
+impl std::error::Error for BackendStartupError {}
+
 struct BackendState {
     child: Mutex<Option<Child>>,
-    port: u16,
+    port: AtomicU16,
+    shutting_down: AtomicBool,
+    // Set while a relocation, profile switch or performance restart replaces the backend;
+    // the supervisor must not restart it then.
+    restarting: AtomicBool,
 }
 
-fn pick_backend_port(preferred: u16) -> u16 {
-    for offset in 0..=BACKEND_PORT_SEARCH_LIMIT {
+// The listener stays bound until the backend inherits it, so no other process can take
+// the port in between. None means nothing was free and the backend has to search itself.
+fn reserve_backend_port(preferred: u16, search_limit: u16) -> (u16, Option<TcpListener>) {
+    for offset in 0..=search_limit {
         let candidate = preferred.saturating_add(offset);
         if let Ok(listener) = TcpListener::bind(("127.0.0.1", candidate)) {
-            drop(listener);
-            return candidate;
+            return (candidate, Some(listener));
         }
     }
-    preferred
+    (preferred, None)
+}
+
+fn stop_backend_process(state: &BackendState, deadline: Duration) {
+    state.shutting_down.store(true, Ordering::SeqCst);
+    stop_owned_backend(state, deadline);
 }
 
-fn stop_backend_process(state: &BackendState) {
+// The graceful stop can take up to the shutdown deadline, so it runs off the event loop.
+fn quit_app(app: &AppHandle) {
+    // Flagged right away, so exit events arriving meanwhile do not start a second stop.
+    app.state::<BackendState>()
+        .shutting_down
+        .store(true, Ordering::SeqCst);
+    let app = app.clone();
+    thread::spawn(move || {
+        let state: State<BackendState> = app.state();
+        stop_backend_process(&state, shutdown::shutdown_deadline());
+        app.exit(0);
+    });
+}
+
+// Leaves the lifecycle flags alone, so a relocation can start the backend again.
+fn stop_backend_child(state: &BackendState) {
+    stop_owned_backend(state, shutdown::shutdown_deadline());
+}
+
+fn stop_owned_backend(state: &BackendState, deadline: Duration) {
     if let Ok(mut guard) = state.child.lock() {
         if let Some(mut child) = guard.take() {
-            #[cfg(target_os = "linux")]
-            terminate_process_tree(child.id());
+            let port = state.port.load(Ordering::SeqCst);
+            let outcome = shutdown::request_graceful_shutdown(&mut child, port, deadline);
+            info!(port, "{}", outcome);
+            if !outcome.needs_signals() {
+                let _ = child.wait();
+                return;
+            }
 
-            let _ = child.kill();
-            let _ = child.wait();
+            process_group::terminate(&mut child);
+            match child.wait() {
+                Ok(status) => info!(%status, "backend stopped by signal"),
+                Err(err) => error!(error = %err, "failed to reap backend after signals"),
+            }
         }
     }
 }
 
-#[cfg(target_os = "linux")]
-fn read_child_pids(pid: u32) -> Vec<u32> {
-    let children_file = format!("/proc/{0}/task/{0}/children", pid);
-    let content = match std::fs::read_to_string(children_file) {
-        Ok(content) => content,
-        Err(_) => return Vec::new(),
-    };
+// Refuses (and reaps) the child when shutdown already started, so it is never orphaned.
+fn install_backend_child(state: &BackendState, mut child: Child) -> bool {
+    if let Ok(mut guard) = state.child.lock() {
+        if !state.shutting_down.load(Ordering::SeqCst) {
+            *guard = Some(child);
+            return true;
+        }
+    }
 
-    content
-        .split_whitespace()
-        .filter_map(|part| part.parse::<u32>().ok())
-        .collect()
+    process_group::terminate(&mut child);
+    let _ = child.wait();
+    false
 }
 
-#[cfg(target_os = "linux")]
-fn collect_process_tree(root_pid: u32) -> Vec<u32> {
-    let mut visited: HashSet<u32> = HashSet::new();
-    let mut stack = vec![root_pid];
-    let mut ordered = Vec::new();
-
-    while let Some(pid) = stack.pop() {
-        if !visited.insert(pid) {
-            continue;
-        }
-        ordered.push(pid);
-
-        for child in read_child_pids(pid) {
-            stack.push(child);
-        }
+fn restart_backend_child(state: &BackendState) -> Result<(), String> {
+    let port = state.port.load(Ordering::SeqCst);
+    let listener = match backend_socket::unix_socket_path() {
+        Some(_) => None,
+        None => TcpListener::bind(("127.0.0.1", port)).ok(),
+    };
+    let (child, port) =
+        spawn_release_backend(port, listener, &|_| {}).map_err(|err| err.to_string())?;
+    if !install_backend_child(state, child) {
+        return Err("the app is shutting down".to_string());
     }
-
-    ordered
+    state.port.store(port, Ordering::SeqCst);
+    Ok(())
 }
 
 #[cfg(target_os = "linux")]
@@ -91,30 +235,6 @@ fn pid_exists(pid: u32) -> bool {
     PathBuf::from(format!("/proc/{}", pid)).exists()
 }
 
-#[cfg(target_os = "linux")]
-fn terminate_process_tree(root_pid: u32) {
-    let mut pids = collect_process_tree(root_pid);
-    pids.reverse();
-
-    for pid in &pids {
-        let _ = Command::new("kill")
-            .arg("-TERM")
-            .arg(pid.to_string())
-            .status();
-    }
-
-    thread::sleep(Duration::from_millis(400));
-
-    for pid in &pids {
-        if pid_exists(*pid) {
-            let _ = Command::new("kill")
-                .arg("-KILL")
-                .arg(pid.to_string())
-                .status();
-        }
-    }
-}
-
 #[cfg(target_os = "linux")]
 fn read_gsettings_string(schema: &str, key: &str) -> Option<String> {
     let output = Command::new("gsettings")
@@ -168,6 +288,7 @@ fn start_linux_theme_watcher(app: tauri::AppHandle) {
         loop {
             if let Some(current_theme) = detect_linux_system_theme() {
                 if last_theme.as_deref() != Some(current_theme.as_str()) {
+                    debug!(theme = %current_theme, "linux system theme changed");
                     let _ = app.emit_all("locus://linux-system-theme-changed", current_theme.clone());
                     last_theme = Some(current_theme);
                 }
@@ -178,58 +299,29 @@ fn start_linux_theme_watcher(app: tauri::AppHandle) {
     });
 }
 
-fn backend_healthcheck_once(port: u16) -> bool {
-    let addr = format!("127.0.0.1:{}", port);
-    let mut stream = match TcpStream::connect_timeout(
-        &addr.parse().expect("invalid backend socket address"),
-        Duration::from_millis(500),
-    ) {
-        Ok(stream) => stream,
-        Err(_) => return false,
-    };
-
-    let _ = stream.set_read_timeout(Some(Duration::from_millis(500)));
-    let _ = stream.set_write_timeout(Some(Duration::from_millis(500)));
-
-    let request = b"GET /health HTTP/1.1\r\nHost: 127.0.0.1\r\nConnection: close\r\n\r\n";
-    if stream.write_all(request).is_err() {
-        return false;
-    }
-
-    let mut response = [0_u8; 256];
-    match stream.read(&mut response) {
-        Ok(0) => false,
-        Ok(n) => {
-            let text = String::from_utf8_lossy(&response[..n]);
-            text.starts_with("HTTP/1.1 200") || text.starts_with("HTTP/1.0 200")
-        }
-        Err(_) => false,
-    }
-}
-
-fn resolve_backend_bin_path() -> PathBuf {
-    let current_exe = std::env::current_exe().expect("failed to resolve current executable");
-    let exe_dir = current_exe
-        .parent()
-        .expect("current executable has no parent directory");
+fn resolve_backend_bin_path() -> Result<PathBuf, BackendStartupError> {
+    let current_exe = std::env::current_exe()
+        .map_err(|err| BackendStartupError::ExecutableDirUnavailable(err.to_string()))?;
+    let exe_dir = current_exe.parent().ok_or_else(|| {
+        BackendStartupError::ExecutableDirUnavailable(String::from(
+            "current executable has no parent directory",
+        ))
+    })?;
 
     let direct = exe_dir.join("locus-backend");
     if direct.exists() {
-        return direct;
+        return Ok(direct);
     }
 
     #[cfg(target_os = "windows")]
     {
         let windows_bin = exe_dir.join("locus-backend.exe");
         if windows_bin.exists() {
-            return windows_bin;
+            return Ok(windows_bin);
         }
     }
 
-    panic!(
-        "failed to locate backend executable next to app binary: {}",
-        direct.display()
-    );
+    Err(BackendStartupError::BinaryNotFound { path: direct })
 }
 
 fn resolve_optional_window_probe_bin_path() -> Option<PathBuf> {
@@ -252,7 +344,48 @@ fn resolve_optional_window_probe_bin_path() -> Option<PathBuf> {
     None
 }
 
+// LOCUS_DATA_DIR wins over locus.toml, which wins over the pointer a relocation leaves;
+// both can only point elsewhere from their own (base) directory.
+fn resolve_default_profile_dir() -> PathBuf {
+    let base_dir = resolve_base_data_dir();
+    let explicit = std::env::var("LOCUS_DATA_DIR")
+        .map(|value| !value.trim().is_empty())
+        .unwrap_or(false);
+    match config::shell_config().paths.data_dir.clone() {
+        _ if explicit => base_dir,
+        Some(configured) => configured,
+        None => relocate::read_data_dir_pointer(&base_dir).unwrap_or(base_dir),
+    }
+}
+
+// Named profiles live under the base dir. Resolved once so a settings reload never moves
+// a running app; only a relocation or profile switch changes it.
+fn locus_data_dir_cell() -> &'static RwLock<PathBuf> {
+    static DATA_DIR: OnceLock<RwLock<PathBuf>> = OnceLock::new();
+    DATA_DIR.get_or_init(|| {
+        let data_dir = match profiles::active_profile() {
+            Some(name) => profiles::profile_data_dir(&resolve_base_data_dir(), &name),
+            None => resolve_default_profile_dir(),
+        };
+        RwLock::new(data_dir)
+    })
+}
+
 fn resolve_locus_data_dir() -> PathBuf {
+    match locus_data_dir_cell().read() {
+        Ok(guard) => guard.clone(),
+        Err(poisoned) => poisoned.into_inner().clone(),
+    }
+}
+
+fn set_locus_data_dir(data_dir: PathBuf) {
+    logging::set_log_dir(&backend_logs::backend_log_dir(&data_dir));
+    if let Ok(mut guard) = locus_data_dir_cell().write() {
+        *guard = data_dir;
+    }
+}
+
+fn resolve_base_data_dir() -> PathBuf {
     if let Ok(explicit) = std::env::var("LOCUS_DATA_DIR") {
         let trimmed = explicit.trim();
         if !trimmed.is_empty() {
@@ -328,7 +461,12 @@ fn resolve_locus_data_dir() -> PathBuf {
 #[cfg(target_os = "windows")]
 fn apply_release_spawn_flags(command: &mut Command) {
     const CREATE_NO_WINDOW: u32 = 0x08000000;
-    command.creation_flags(CREATE_NO_WINDOW);
+    const BELOW_NORMAL_PRIORITY_CLASS: u32 = 0x00004000;
+    let mut flags = CREATE_NO_WINDOW;
+    if performance::is_throttled() {
+        flags |= BELOW_NORMAL_PRIORITY_CLASS;
+    }
+    command.creation_flags(flags);
 }
 
 #[cfg(not(target_os = "windows"))]
@@ -337,130 +475,359 @@ fn apply_release_spawn_flags(_command: &mut Command) {
 
 fn wait_for_backend_ready_or_exit(
     child: &mut Child,
-    port: u16,
+    port: &mut u16,
+    listener_passed: bool,
     timeout: Duration,
-) -> Result<(), String> {
+    on_phase: &dyn Fn(StartupPhase),
+) -> Result<(), BackendStartupError> {
     let deadline = Instant::now() + timeout;
+    let mut reported = StartupPhase::WaitingForHealth;
+    on_phase(reported);
+    let data_dir = resolve_locus_data_dir();
+    let poll_interval_ms = config::shell_config().backend.poll_interval_ms;
+    let mut version_checked = false;
+
     while Instant::now() < deadline {
-        if backend_healthcheck_once(port) {
-            return Ok(());
+        // A backend without LISTEN_FDS support leaves the inherited listener idle, binds
+        // a port of its own and advertises it in backend.json; follow it there.
+        if listener_passed {
+            if let Some(advertised) = discovery::advertised_port(&data_dir, child.id()) {
+                if advertised != *port {
+                    warn!(
+                        inherited = *port,
+                        port = advertised,
+                        "backend ignored the inherited listener"
+                    );
+                    *port = advertised;
+                }
+            }
+        }
+
+        if let Some(health) = fetch_backend_health(*port) {
+            if !version_checked {
+                info!("{}", compat::describe_combination(health.version.as_ref()));
+                compat::check_backend_version(health.version.as_ref())
+                    .map_err(BackendStartupError::Incompatible)?;
+                version_checked = true;
+            }
+
+            // Older backends report no watcher detail; treat an unknown watcher as ready.
+            let db_ready = health.db == ComponentStatus::Ready;
+            let watcher_ready = matches!(
+                health.watcher,
+                ComponentStatus::Ready | ComponentStatus::Unknown
+            );
+
+            if db_ready && reported < StartupPhase::DbReady {
+                reported = StartupPhase::DbReady;
+                on_phase(reported);
+            }
+            if db_ready && watcher_ready {
+                on_phase(StartupPhase::WatcherReady);
+                return Ok(());
+            }
         }
 
         match child.try_wait() {
             Ok(Some(status)) => {
-                return Err(format!(
-                    "backend exited before healthcheck on port {} with status {}",
-                    port, status
-                ));
+                return Err(BackendStartupError::ExitedBeforeReady {
+                    port: *port,
+                    status,
+                });
             }
             Ok(None) => {}
-            Err(err) => {
-                return Err(format!(
-                    "failed while polling backend process status: {}",
-                    err
-                ));
+            Err(source) => {
+                return Err(BackendStartupError::StatusPollFailed {
+                    port: *port,
+                    source,
+                });
             }
         }
 
-        thread::sleep(Duration::from_millis(BACKEND_POLL_INTERVAL_MS));
+        thread::sleep(Duration::from_millis(poll_interval_ms));
     }
 
-    Err(format!(
-        "backend failed to become ready on port {} within {}s",
-        port, BACKEND_STARTUP_TIMEOUT_SECS
-    ))
+    Err(BackendStartupError::ReadyTimeout {
+        port: *port,
+        timeout,
+    })
 }
 
-fn start_release_backend(port: u16) -> Child {
-    let backend_bin = resolve_backend_bin_path();
+fn spawn_release_backend(
+    mut port: u16,
+    listener: Option<TcpListener>,
+    on_phase: &dyn Fn(StartupPhase),
+) -> Result<(Child, u16), BackendStartupError> {
+    on_phase(StartupPhase::Spawning);
+    let backend_bin = resolve_backend_bin_path()?;
+    sidecar_integrity::verify_sidecar(&backend_bin, sidecar_integrity::BACKEND_SHA256)
+        .map_err(BackendStartupError::SidecarIntegrity)?;
     let data_dir = resolve_locus_data_dir();
-    let _ = std::fs::create_dir_all(&data_dir);
+    let _ = data_dir_permissions::create_private_dir(&data_dir);
 
-    let mut backend_command = Command::new(&backend_bin);
+    let log_dir = backend_logs::backend_log_dir(&data_dir);
+    let settings = config::shell_config();
+
+    let mut backend_command = performance::backend_command(&backend_bin);
     backend_command
+        .envs(&settings.backend.env)
         .env("LOCUS_PORT", port.to_string())
         .env("LOCUS_DATA_DIR", data_dir)
-        .env("LOCUS_PARENT_PID", std::process::id().to_string());
+        .env("LOCUS_PARENT_PID", std::process::id().to_string())
+        .env(api_token::API_TOKEN_ENV, api_token::api_token());
+
+    if let Some(socket_path) = backend_socket::unix_socket_path() {
+        backend_socket::prepare_socket_dir(socket_path).map_err(|source| {
+            BackendStartupError::SocketUnavailable {
+                path: socket_path.to_path_buf(),
+                source,
+            }
+        })?;
+        backend_command.env(backend_socket::BACKEND_SOCKET_ENV, socket_path);
+    }
 
+    // The probe is optional: a copy that fails verification is left out, not fatal.
     if let Some(window_probe_bin) = resolve_optional_window_probe_bin_path() {
-        backend_command.env("LOCUS_WINDOW_PROBE", window_probe_bin);
+        match sidecar_integrity::verify_sidecar(
+            &window_probe_bin,
+            sidecar_integrity::WINDOW_PROBE_SHA256,
+        ) {
+            Ok(()) => {
+                backend_command.env("LOCUS_WINDOW_PROBE", window_probe_bin);
+            }
+            Err(err) => warn!(error = %err, "not using the window probe"),
+        }
     }
 
+    let listener_passed = match &listener {
+        Some(listener) => listen_fds::pass_listener(&mut backend_command, listener),
+        None => false,
+    };
+
     apply_release_spawn_flags(&mut backend_command);
+    process_group::isolate(&mut backend_command);
+    performance::apply_spawn_priority(&mut backend_command);
 
-    let mut child = backend_command
+    let spawned = backend_command
         .stdin(Stdio::null())
-        .stdout(Stdio::null())
-        .stderr(Stdio::null())
-        .spawn()
-        .unwrap_or_else(|err| {
-            panic!(
-                "failed to spawn backend binary '{}': {}",
-                backend_bin.display(),
-                err
-            )
-        });
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn();
+    // The child owns its copy now; ours only has to live until the fork.
+    drop(listener);
+    let mut child = spawned.map_err(|source| BackendStartupError::SpawnFailed {
+        path: backend_bin.clone(),
+        source,
+    })?;
+    info!(pid = child.id(), port, "spawned backend sidecar");
+
+    if let Err(err) = backend_logs::capture_backend_output(&mut child, &log_dir) {
+        warn!(dir = %log_dir.display(), error = %err, "failed to open backend log");
+    }
 
-    if let Err(message) = wait_for_backend_ready_or_exit(
+    if let Err(err) = wait_for_backend_ready_or_exit(
         &mut child,
-        port,
-        Duration::from_secs(BACKEND_STARTUP_TIMEOUT_SECS),
+        &mut port,
+        listener_passed,
+        Duration::from_secs(settings.backend.startup_timeout_secs),
+        on_phase,
     ) {
-        let _ = child.kill();
+        process_group::terminate(&mut child);
         let _ = child.wait();
-        panic!("{}", message);
+        return Err(err);
     }
 
-    child
+    info!(pid = child.id(), port, "backend sidecar ready");
+    Ok((child, port))
 }
 
-fn main() {
-    let selected_port = if cfg!(debug_assertions) {
-        DEFAULT_BACKEND_PORT
-    } else {
-        pick_backend_port(DEFAULT_BACKEND_PORT)
-    };
+fn create_main_window(app: &AppHandle) -> tauri::Result<Window> {
+    let window = WindowBuilder::new(app, "main", WindowUrl::App("index.html".into()))
+        .title("Locus")
+        .inner_size(1100.0, 750.0)
+        .resizable(true)
+        .decorations(false)
+        .build()?;
+    let _ = window.maximize();
+    Ok(window)
+}
 
-    // In release, guarantee backend readiness before creating the UI window.
-    let backend_child = if cfg!(debug_assertions) {
-        None
-    } else {
-        Some(start_release_backend(selected_port))
-    };
+fn build_tray_menu(
+    health_label: &str,
+    recent_files: &recent_files::RecentFilesState,
+) -> SystemTrayMenu {
+    let mut performance_item = CustomMenuItem::new(
+        performance::TRAY_PERFORMANCE_ITEM_ID.to_string(),
+        "Performance mode",
+    );
+    performance_item.selected = !performance::is_throttled();
 
     let tray_menu = SystemTrayMenu::new()
         .add_item(CustomMenuItem::new("show".to_string(), "Show"))
+        .add_item(
+            CustomMenuItem::new(health::TRAY_HEALTH_ITEM_ID.to_string(), health_label).disabled(),
+        )
+        .add_item(performance_item)
+        .add_submenu(profiles::tray_submenu())
+        .add_native_item(SystemTrayMenuItem::Separator)
+        .add_submenu(recent_files::tray_submenu(recent_files));
+    tray_controls::add_tray_items(tray_menu)
         .add_native_item(SystemTrayMenuItem::Separator)
-        .add_item(CustomMenuItem::new("quit".to_string(), "Quit"));
-    let system_tray = SystemTray::new().with_menu(tray_menu);
+        .add_item(CustomMenuItem::new(
+            "reload_settings".to_string(),
+            "Reload settings",
+        ))
+        .add_item(CustomMenuItem::new("quit".to_string(), "Quit"))
+}
+
+// Tauri 1 cannot add items to a live menu, so dynamic entries mean a whole new menu.
+fn rebuild_tray_menu(app: &AppHandle) {
+    let health_label = health::last_health_label(&app.state::<HealthState>())
+        .unwrap_or_else(|| "Backend: starting".to_string());
+    let menu = build_tray_menu(&health_label, &app.state());
+    if let Err(err) = app.tray_handle().set_menu(menu) {
+        warn!(error = %err, "failed to rebuild tray menu");
+        return;
+    }
+    tray_controls::restore_tray_items(app);
+}
+
+fn main() {
+    logging::init();
+    // A bad --profile must fail before anything resolves the data dir.
+    if let Err(err) = profiles::init_from_args() {
+        error!("{}", err);
+        std::process::exit(2);
+    }
+    crash_report::install_panic_hook();
+
+    let context = tauri::generate_context!();
+    if let Err(err) = config::init() {
+        error!(error = %err, "invalid settings file");
+        startup_dialog::run_config_recovery(context, err);
+        return;
+    }
+
+    let instance_guard = match single_instance::acquire(
+        &resolve_locus_data_dir(),
+        &single_instance::LaunchRequest::current(),
+    ) {
+        Ok(single_instance::InstanceRole::Primary(guard)) => Some(guard),
+        Ok(single_instance::InstanceRole::Secondary) => {
+            info!("Locus is already running; handed launch over to it");
+            return;
+        }
+        Err(err) => {
+            warn!(error = %err, "single-instance lock unavailable");
+            None
+        }
+    };
+    logging::set_log_dir(&backend_logs::backend_log_dir(&resolve_locus_data_dir()));
+    logging::apply_level(&config::shell_config().logs.level);
+
+    performance::init(&config::shell_config().performance);
+    let system_tray = SystemTray::new().with_menu(build_tray_menu(
+        "Backend: starting",
+        &recent_files::RecentFilesState::default(),
+    ));
 
     tauri::Builder::default()
         .manage(BackendState {
-            child: Mutex::new(backend_child),
-            port: selected_port,
+            child: Mutex::new(None),
+            port: AtomicU16::new(config::shell_config().backend.port),
+            shutting_down: AtomicBool::new(false),
+            restarting: AtomicBool::new(false),
         })
+        .manage(StartupState::default())
+        .manage(HealthState::default())
+        .manage(resource_usage::ResourceUsageState::default())
+        .manage(tray_controls::TrayControlsState::default())
+        .manage(recent_files::RecentFilesState::default())
+        .manage(single_instance::ActiveInstance::new(instance_guard))
+        .invoke_handler(tauri::generate_handler![
+            backend_logs::get_backend_log_tail,
+            startup::get_startup_progress,
+            health::get_backend_health,
+            backend_socket::proxy_backend_request,
+            resource_usage::get_resource_usage,
+            config::get_shell_config,
+            config::reload_shell_config_command,
+            relocate::relocate_data_dir,
+            profiles::list_profiles,
+            profiles::switch_profile_command,
+            logging::get_shell_log,
+            tray_controls::get_tray_controls
+        ])
         .system_tray(system_tray)
         .on_system_tray_event(|app, event| {
             if let SystemTrayEvent::MenuItemClick { id, .. } = event {
+                info!(item = %id, "tray item clicked");
                 match id.as_str() {
-                    "quit" => {
-                        let state: State<BackendState> = app.state();
-                        stop_backend_process(&state);
-                        app.exit(0);
-                    }
+                    "quit" => quit_app(app),
                     "show" => {
                         if let Some(window) = app.get_window("main") {
-                            window.show().unwrap();
+                            if let Err(err) = window.show() {
+                                warn!(error = %err, "failed to show main window");
+                            }
+                        }
+                    }
+                    performance::TRAY_PERFORMANCE_ITEM_ID => {
+                        performance::toggle_performance_mode(app);
+                    }
+                    id if id.starts_with(recent_files::TRAY_RESTORE_ITEM_PREFIX) => {
+                        let app = app.clone();
+                        let id = id.to_string();
+                        thread::spawn(move || recent_files::restore_from_tray(&app, &id));
+                    }
+                    id if tray_controls::is_tray_item(id) => {
+                        let app = app.clone();
+                        let id = id.to_string();
+                        thread::spawn(move || tray_controls::handle_tray_item(&app, &id));
+                    }
+                    profiles::TRAY_NEW_PROFILE_ITEM_ID => profiles::request_new_profile(app),
+                    id if id.starts_with(profiles::TRAY_PROFILE_ITEM_PREFIX) => {
+                        let app = app.clone();
+                        let name = id[profiles::TRAY_PROFILE_ITEM_PREFIX.len()..].to_string();
+                        thread::spawn(move || {
+                            if let Err(err) = profiles::switch_profile(&app, &name) {
+                                error!(error = %err, "profile switch failed");
+                                MessageDialogBuilder::new("Locus profiles", err.to_string())
+                                    .kind(MessageDialogKind::Error)
+                                    .show(|_| {});
+                            }
+                        });
+                    }
+                    "reload_settings" => {
+                        if let Err(err) = config::reload_shell_config() {
+                            warn!(error = %err, "keeping previous settings");
+                            MessageDialogBuilder::new("Locus settings", err.to_string())
+                                .kind(MessageDialogKind::Error)
+                                .show(|_| {});
                         }
                     }
                     _ => {}
                 }
             }
         })
-        .setup(|app| {
-            if cfg!(debug_assertions) {
+        .setup(move |app| {
+            logging::start_log_forwarder(app.handle());
+            crash_report::attach_app(app.handle());
+            crash_report::offer_previous_crash_report();
+            app.state::<single_instance::ActiveInstance>()
+                .start_handoff_listener(app.handle());
+
+            let backend_port = config::shell_config().backend.port;
+            if cfg!(debug_assertions) && dev_backend::spawn_requested() {
+                info!(port = backend_port, "dev mode: spawning backend/service_entry.py with reload");
+                dev_backend::start_dev_backend(app.handle());
+            } else if cfg!(debug_assertions) {
                 // In dev mode, we assume the user is running the backend manually.
-                println!("[tauri] Dev mode: Skipping sidecar spawn, expecting backend on port {}", DEFAULT_BACKEND_PORT);
+                info!(port = backend_port, "dev mode: skipping sidecar spawn, expecting a backend started by hand");
+                create_main_window(&app.handle())?;
+            } else {
+                // In release, show the splash right away and open the main window once the backend is ready.
+                startup::create_splash_window(&app.handle())?;
+                startup::start_release_startup(app.handle());
             }
 
             #[cfg(target_os = "linux")]
@@ -468,29 +835,41 @@ fn main() {
                 start_linux_theme_watcher(app.handle());
             }
 
-            if let Some(window) = app.get_window("main") {
-                let _ = window.set_decorations(false);
-                let _ = window.maximize();
-            }
+            health::start_health_monitor(app.handle());
+            tray_controls::start_tray_controls_monitor(app.handle());
+            recent_files::start_recent_files_monitor(app.handle());
+            resource_usage::start_resource_monitor(app.handle());
 
             Ok(())
         })
         .on_page_load(|window, _payload| {
             let state: State<BackendState> = window.state();
-            let backend_url = format!("http://127.0.0.1:{}", state.port);
+            let backend_url = match backend_socket::unix_socket_path() {
+                Some(_) => backend_socket::PROXY_BASE_URL.to_string(),
+                None => format!("http://127.0.0.1:{}", state.port.load(Ordering::SeqCst)),
+            };
+            // The token is deliberately kept out of localStorage so it dies with the launch.
             let script = format!(
-                "window.__LOCUS_BACKEND_URL = '{0}'; window.localStorage.setItem('locus-backend-url', '{0}');",
-                backend_url
+                "window.__LOCUS_BACKEND_URL = '{0}'; window.localStorage.setItem('locus-backend-url', '{0}'); window.__LOCUS_API_TOKEN = '{1}';",
+                backend_url,
+                api_token::api_token()
             );
             let _ = window.eval(script.as_str());
         })
         .on_window_event(|event| {
             match event.event() {
                 WindowEvent::CloseRequested { api, .. } => {
+                    debug!(window = event.window().label(), "close requested");
+                    if event.window().label() == startup::SPLASH_WINDOW_LABEL {
+                        return;
+                    }
                     if let Err(err) = event.window().hide() {
-                        eprintln!("[tauri] failed to hide window on close request: {}", err);
+                        warn!(error = %err, "failed to hide window on close request");
                     }
                     api.prevent_close();
+                    if event.window().label() == "main" && !config::shell_config().tray.close_to_tray {
+                        quit_app(&event.window().app_handle());
+                    }
                 }
 
                 WindowEvent::ThemeChanged(theme) => {
@@ -499,18 +878,35 @@ fn main() {
                     } else {
                         "light"
                     };
+                    debug!(window = event.window().label(), theme = payload, "theme changed");
                     let _ = event.window().emit("locus://theme-changed", payload);
                 }
 
+                WindowEvent::Focused(focused) => {
+                    debug!(window = event.window().label(), focused, "window focus changed");
+                }
+
                 _ => {}
             }
         })
-        .build(tauri::generate_context!())
+        .build(context)
         .expect("error while building tauri application")
         .run(|app_handle, event| {
-            if matches!(event, RunEvent::ExitRequested { .. } | RunEvent::Exit) {
-                let state: State<BackendState> = app_handle.state();
-                stop_backend_process(&state);
+            let state: State<BackendState> = app_handle.state();
+            // quit_app already stopped the backend, or is still stopping it.
+            if state.shutting_down.load(Ordering::SeqCst) {
+                return;
+            }
+            match event {
+                RunEvent::ExitRequested { api, .. } => {
+                    api.prevent_exit();
+                    quit_app(app_handle);
+                }
+                RunEvent::Exit => {
+                    let deadline = Duration::from_secs(shutdown::EXIT_SHUTDOWN_DEADLINE_SECS);
+                    stop_backend_process(&state, deadline);
+                }
+                _ => {}
             }
         });
 }
@@ -521,7 +917,7 @@ mod tests {
     use std::net::TcpListener;
 
     #[test]
-    fn pick_backend_port_uses_preferred_when_free() {
+    fn reserve_backend_port_uses_preferred_when_free() {
         let listener = TcpListener::bind(("127.0.0.1", 0)).expect("ephemeral bind failed");
         let preferred = listener
             .local_addr()
@@ -529,12 +925,13 @@ mod tests {
             .port();
         drop(listener);
 
-        let selected = pick_backend_port(preferred);
+        let (selected, listener) = reserve_backend_port(preferred, BACKEND_PORT_SEARCH_LIMIT);
         assert_eq!(selected, preferred);
+        assert!(listener.is_some());
     }
 
     #[test]
-    fn pick_backend_port_moves_forward_when_preferred_is_busy() {
+    fn reserve_backend_port_moves_forward_when_preferred_is_busy() {
         let busy_listener =
             TcpListener::bind(("127.0.0.1", 0)).expect("failed to reserve busy port");
         let preferred = busy_listener
@@ -542,7 +939,7 @@ mod tests {
             .expect("failed to read busy port")
             .port();
 
-        let selected = pick_backend_port(preferred);
+        let (selected, _listener) = reserve_backend_port(preferred, BACKEND_PORT_SEARCH_LIMIT);
         assert_ne!(selected, preferred);
         assert!(selected >= preferred);
         assert!(selected <= preferred.saturating_add(BACKEND_PORT_SEARCH_LIMIT));
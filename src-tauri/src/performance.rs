@@ -0,0 +1,328 @@
+use std::io;
+use std::path::Path;
+use std::process::Command;
+#[cfg(target_os = "linux")]
+use std::sync::atomic::AtomicU32;
+use std::sync::atomic::{AtomicBool, Ordering};
+#[cfg(target_os = "linux")]
+use std::sync::Mutex;
+use std::thread;
+
+use tauri::{AppHandle, Manager, State};
+use tracing::{error, info, warn};
+
+use crate::config::{self, PerformanceConfig};
+use crate::{restart_backend_child, stop_backend_child, BackendState};
+
+pub const TRAY_PERFORMANCE_ITEM_ID: &str = "performance_mode";
+
+static THROTTLED: AtomicBool = AtomicBool::new(false);
+
+#[cfg(target_os = "linux")]
+static SCOPE_SEQUENCE: AtomicU32 = AtomicU32::new(0);
+#[cfg(target_os = "linux")]
+static CURRENT_SCOPE: Mutex<Option<String>> = Mutex::new(None);
+
+#[cfg(target_os = "linux")]
+const IOPRIO_CLASS_SHIFT: libc::c_int = 13;
+#[cfg(target_os = "linux")]
+const IOPRIO_CLASS_IDLE: libc::c_int = 3;
+#[cfg(target_os = "linux")]
+const IOPRIO_WHO_PROCESS: libc::c_int = 1;
+#[cfg(target_os = "linux")]
+const IOPRIO_WHO_PGRP: libc::c_int = 2;
+
+pub fn init(settings: &PerformanceConfig) {
+    THROTTLED.store(settings.throttled, Ordering::SeqCst);
+}
+
+pub fn is_throttled() -> bool {
+    THROTTLED.load(Ordering::SeqCst)
+}
+
+#[cfg(target_os = "linux")]
+fn ioprio_value(idle: bool) -> libc::c_int {
+    // Class NONE hands IO scheduling back to the nice level, i.e. the kernel default.
+    if idle {
+        IOPRIO_CLASS_IDLE << IOPRIO_CLASS_SHIFT
+    } else {
+        0
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn cgroup_limits_requested(settings: &PerformanceConfig) -> bool {
+    settings.cgroup && (settings.cpu_quota_percent.is_some() || settings.memory_max_mb.is_some())
+}
+
+#[cfg(target_os = "linux")]
+fn systemd_run_available() -> bool {
+    Path::new("/sys/fs/cgroup/cgroup.controllers").exists()
+        && std::env::var_os("PATH")
+            .map(|paths| std::env::split_paths(&paths).any(|dir| dir.join("systemd-run").is_file()))
+            .unwrap_or(false)
+}
+
+#[cfg(target_os = "linux")]
+fn scope_properties(settings: &PerformanceConfig, throttled: bool) -> Vec<String> {
+    let cpu_quota = settings
+        .cpu_quota_percent
+        .filter(|_| throttled)
+        .map(|percent| format!("{}%", percent))
+        .unwrap_or_default();
+    let memory_max = settings
+        .memory_max_mb
+        .filter(|_| throttled)
+        .map(|megabytes| format!("{}M", megabytes))
+        .unwrap_or_else(|| "infinity".to_string());
+    vec![
+        format!("CPUQuota={}", cpu_quota),
+        format!("MemoryMax={}", memory_max),
+    ]
+}
+
+// `systemd-run --scope` execs the backend itself, so its pid, process group and
+// inherited listener stay the same.
+#[cfg(target_os = "linux")]
+pub fn backend_command(backend_bin: &Path) -> Command {
+    if let Ok(mut current) = CURRENT_SCOPE.lock() {
+        *current = None;
+    }
+    let settings = &config::shell_config().performance;
+    if !cgroup_limits_requested(settings) {
+        return Command::new(backend_bin);
+    }
+    if !systemd_run_available() {
+        warn!("cgroup limits need systemd-run and cgroup v2; starting backend without them");
+        return Command::new(backend_bin);
+    }
+
+    let unit = format!(
+        "locus-backend-{}-{}.scope",
+        std::process::id(),
+        SCOPE_SEQUENCE.fetch_add(1, Ordering::SeqCst)
+    );
+    let mut command = Command::new("systemd-run");
+    command.args(["--user", "--scope", "--quiet", "--collect"]);
+    command.arg(format!("--unit={}", unit));
+    for property in scope_properties(settings, is_throttled()) {
+        command.arg("-p").arg(property);
+    }
+    command.arg("--").arg(backend_bin);
+
+    if let Ok(mut current) = CURRENT_SCOPE.lock() {
+        *current = Some(unit);
+    }
+    command
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn backend_command(backend_bin: &Path) -> Command {
+    Command::new(backend_bin)
+}
+
+#[cfg(unix)]
+pub fn apply_spawn_priority(command: &mut Command) {
+    use std::os::unix::process::CommandExt;
+
+    if !is_throttled() {
+        return;
+    }
+    let settings = &config::shell_config().performance;
+    let nice = settings.nice;
+    #[cfg(target_os = "linux")]
+    let idle_io = settings.idle_io;
+
+    // SAFETY: only async-signal-safe syscalls run between fork and exec.
+    unsafe {
+        command.pre_exec(move || {
+            if libc::setpriority(libc::PRIO_PROCESS, 0, nice) == -1 {
+                return Err(io::Error::last_os_error());
+            }
+            #[cfg(target_os = "linux")]
+            if idle_io {
+                libc::syscall(
+                    libc::SYS_ioprio_set,
+                    IOPRIO_WHO_PROCESS,
+                    0,
+                    ioprio_value(true),
+                );
+            }
+            Ok(())
+        });
+    }
+}
+
+#[cfg(not(unix))]
+pub fn apply_spawn_priority(_command: &mut Command) {}
+
+// The error is the nice change: lowering priority always works, but raising it again
+// needs CAP_SYS_NICE or RLIMIT_NICE headroom most desktops do not grant.
+#[cfg(unix)]
+fn apply_to_running_backend(pgid: u32, throttled: bool) -> io::Result<()> {
+    let settings = &config::shell_config().performance;
+    let nice = if throttled { settings.nice } else { 0 };
+
+    // SAFETY: setpriority has no memory-safety preconditions.
+    let result = unsafe { libc::setpriority(libc::PRIO_PGRP, pgid as libc::id_t, nice) };
+    let reniced = if result == -1 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(())
+    };
+
+    #[cfg(target_os = "linux")]
+    {
+        if settings.idle_io {
+            // SAFETY: ioprio_set only reads its integer arguments.
+            let result = unsafe {
+                libc::syscall(
+                    libc::SYS_ioprio_set,
+                    IOPRIO_WHO_PGRP,
+                    pgid as libc::c_int,
+                    ioprio_value(throttled),
+                )
+            };
+            if result == -1 {
+                warn!(
+                    error = %std::io::Error::last_os_error(),
+                    "could not change backend IO class"
+                );
+            }
+        }
+
+        let unit = CURRENT_SCOPE
+            .lock()
+            .ok()
+            .and_then(|current| current.clone());
+        if let Some(unit) = unit {
+            let status = Command::new("systemctl")
+                .args(["--user", "set-property", "--runtime", unit.as_str()])
+                .args(scope_properties(settings, throttled))
+                .status();
+            if !matches!(status, Ok(status) if status.success()) {
+                warn!(unit = %unit, "could not update cgroup limits");
+            }
+        }
+    }
+    reniced
+}
+
+#[cfg(not(unix))]
+fn apply_to_running_backend(_pgid: u32, _throttled: bool) -> io::Result<()> {
+    warn!("backend priority changes apply after the next backend restart");
+    Ok(())
+}
+
+fn restart_at_full_speed(backend: &BackendState) {
+    if backend.restarting.swap(true, Ordering::SeqCst) {
+        warn!("backend is already restarting; full speed applies once it is back");
+        return;
+    }
+    info!("restarting backend to lift its lowered priority");
+    stop_backend_child(backend);
+    let result = restart_backend_child(backend);
+    backend.restarting.store(false, Ordering::SeqCst);
+    if let Err(err) = result {
+        error!(error = %err, "backend restart for performance mode failed");
+    }
+}
+
+fn apply_to_backend(app: &AppHandle, throttled: bool) {
+    let state: State<BackendState> = app.state();
+    let pid = state
+        .child
+        .lock()
+        .ok()
+        .and_then(|guard| guard.as_ref().map(|child| child.id()));
+    if let Some(pid) = pid {
+        match apply_to_running_backend(pid, throttled) {
+            Ok(()) => {}
+            Err(err) if throttled => {
+                warn!(error = %err, "could not lower backend priority until its next restart");
+            }
+            Err(err) => {
+                info!(error = %err, "backend priority cannot be raised in place");
+                restart_at_full_speed(&state);
+            }
+        }
+    }
+}
+
+// "Performance mode" is the full-speed side of the toggle, hence the inverted checkmark.
+pub fn toggle_performance_mode(app: &AppHandle) {
+    let throttled = !THROTTLED.fetch_xor(true, Ordering::SeqCst);
+    info!(
+        throttled,
+        "performance mode {}",
+        if throttled {
+            "off: throttling backend"
+        } else {
+            "on: backend at full speed"
+        }
+    );
+    let _ = app
+        .tray_handle()
+        .get_item(TRAY_PERFORMANCE_ITEM_ID)
+        .set_selected(!throttled);
+
+    let app = app.clone();
+    thread::spawn(move || apply_to_backend(&app, throttled));
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scope_limits_lift_at_full_speed() {
+        let settings = PerformanceConfig {
+            cpu_quota_percent: Some(50),
+            memory_max_mb: Some(2048),
+            ..PerformanceConfig::default()
+        };
+
+        assert_eq!(
+            scope_properties(&settings, true),
+            vec!["CPUQuota=50%".to_string(), "MemoryMax=2048M".to_string()]
+        );
+        assert_eq!(
+            scope_properties(&settings, false),
+            vec!["CPUQuota=".to_string(), "MemoryMax=infinity".to_string()]
+        );
+    }
+
+    fn nice_of(pid: u32) -> Option<i32> {
+        let stat = std::fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?;
+        // Fields after the parenthesised command name; nice is field 19 overall.
+        let fields: Vec<&str> = stat.rsplit_once(')')?.1.split_whitespace().collect();
+        fields.get(16)?.parse().ok()
+    }
+
+    #[test]
+    fn full_speed_is_restored_in_place_or_reported_for_a_restart() {
+        use std::os::unix::process::CommandExt;
+
+        let mut child = Command::new("sleep")
+            .arg("5")
+            .process_group(0)
+            .spawn()
+            .expect("failed to spawn sleep");
+        let pid = child.id();
+
+        apply_to_running_backend(pid, true).expect("lowering priority is always allowed");
+        assert_eq!(nice_of(pid), Some(config::shell_config().performance.nice));
+
+        // Unprivileged, the kernel refuses and the toggle restarts the backend instead.
+        match apply_to_running_backend(pid, false) {
+            Ok(()) => assert_eq!(nice_of(pid), Some(0)),
+            Err(err) => assert!(matches!(
+                err.raw_os_error(),
+                Some(libc::EACCES) | Some(libc::EPERM)
+            )),
+        }
+
+        let _ = child.kill();
+        let _ = child.wait();
+    }
+}
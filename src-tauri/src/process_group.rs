@@ -0,0 +1,103 @@
+use std::process::{Child, Command};
+#[cfg(unix)]
+use std::thread;
+#[cfg(unix)]
+use std::time::{Duration, Instant};
+
+#[cfg(unix)]
+const GROUP_TERM_GRACE_MS: u64 = 400;
+#[cfg(unix)]
+const GROUP_POLL_INTERVAL_MS: u64 = 25;
+
+// The sidecar leads its own process group, so PyInstaller's bootstrap process and any
+// helper it forks can be signalled together, even after they were reparented to init.
+#[cfg(unix)]
+pub fn isolate(command: &mut Command) {
+    use std::os::unix::process::CommandExt;
+
+    command.process_group(0);
+}
+
+#[cfg(not(unix))]
+pub fn isolate(_command: &mut Command) {}
+
+#[cfg(unix)]
+fn signal_group(pgid: libc::pid_t, signal: libc::c_int) -> bool {
+    // SAFETY: kill has no memory-safety preconditions; a negative pid targets the group.
+    unsafe { libc::kill(-pgid, signal) == 0 }
+}
+
+// Reaps the leader while waiting so its zombie does not keep the group looking alive.
+#[cfg(unix)]
+fn wait_for_group_exit(child: &mut Child, pgid: libc::pid_t, grace: Duration) -> bool {
+    let deadline = Instant::now() + grace;
+    loop {
+        let _ = child.try_wait();
+        if !signal_group(pgid, 0) {
+            return true;
+        }
+        if Instant::now() >= deadline {
+            return false;
+        }
+        thread::sleep(Duration::from_millis(GROUP_POLL_INTERVAL_MS));
+    }
+}
+
+// SIGTERM to the whole group, then SIGKILL whatever is left after a short grace period.
+// The pgid stays reserved while any member (or the unreaped leader) exists, so call this
+// before or right after reaping the leader. The caller still reaps `child`.
+#[cfg(unix)]
+pub fn terminate(child: &mut Child) {
+    let pgid = child.id() as libc::pid_t;
+    if !signal_group(pgid, libc::SIGTERM) {
+        let _ = child.kill();
+        return;
+    }
+    if !wait_for_group_exit(child, pgid, Duration::from_millis(GROUP_TERM_GRACE_MS)) {
+        signal_group(pgid, libc::SIGKILL);
+    }
+    let _ = child.kill();
+}
+
+#[cfg(not(unix))]
+pub fn terminate(child: &mut Child) {
+    let _ = child.kill();
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader};
+    use std::process::Stdio;
+
+    #[test]
+    fn terminate_reaches_reparented_grandchildren() {
+        // The middle shell exits right away, leaving its background sleep orphaned.
+        let mut command = Command::new("sh");
+        command
+            .arg("-c")
+            .arg("sh -c 'sleep 30 & echo $!'; sleep 30")
+            .stdout(Stdio::piped());
+        isolate(&mut command);
+        let mut child = command.spawn().expect("failed to spawn sh");
+
+        let stdout = child.stdout.take().expect("stdout not captured");
+        let mut line = String::new();
+        BufReader::new(stdout)
+            .read_line(&mut line)
+            .expect("failed to read grandchild pid");
+        let grandchild: libc::pid_t = line.trim().parse().expect("bad grandchild pid");
+        let pgid = child.id() as libc::pid_t;
+        // SAFETY: getpgid only reads kernel state for the given pid.
+        assert_eq!(unsafe { libc::getpgid(grandchild) }, pgid);
+
+        terminate(&mut child);
+        let _ = child.wait();
+
+        assert!(wait_for_group_exit(
+            &mut child,
+            pgid,
+            Duration::from_secs(2)
+        ));
+    }
+}
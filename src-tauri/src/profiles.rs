@@ -0,0 +1,331 @@
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::Ordering;
+use std::sync::RwLock;
+
+use serde::Serialize;
+use tauri::{
+    AppHandle, CustomMenuItem, Manager, State, SystemTrayMenu, SystemTrayMenuItem,
+    SystemTraySubmenu,
+};
+use tracing::{info, warn};
+
+use crate::data_dir_permissions::{self, PermissionProblem};
+use crate::{
+    backend_socket, rebuild_tray_menu, resolve_base_data_dir, resolve_default_profile_dir,
+    restart_backend_child, set_locus_data_dir, single_instance, stop_backend_child, BackendState,
+};
+
+pub const TRAY_PROFILE_ITEM_PREFIX: &str = "profile:";
+pub const TRAY_NEW_PROFILE_ITEM_ID: &str = "new_profile";
+pub const PROFILE_CHANGED_EVENT: &str = "locus://profile-changed";
+pub const NEW_PROFILE_EVENT: &str = "locus://new-profile";
+pub const DEFAULT_PROFILE: &str = "default";
+
+pub const PROFILE_ARG: &str = "--profile";
+const PROFILES_DIR_NAME: &str = "profiles";
+const PROFILE_NAME_MAX_LEN: usize = 32;
+
+// None is the default profile, i.e. the data dir Locus used before profiles existed.
+static ACTIVE_PROFILE: RwLock<Option<String>> = RwLock::new(None);
+
+#[derive(Debug)]
+pub enum ProfileError {
+    MissingName,
+    InvalidName(String),
+    InProgress,
+    NotOwned,
+    SocketTransport,
+    Insecure(PermissionProblem),
+    OpenElsewhere(String),
+    Io { path: PathBuf, source: io::Error },
+    Restart { profile: String, message: String },
+}
+
+impl fmt::Display for ProfileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingName => write!(f, "{} needs a profile name", PROFILE_ARG),
+            Self::InvalidName(name) => write!(
+                f,
+                "invalid profile name '{}': use up to {} letters, digits, '-' or '_'",
+                name, PROFILE_NAME_MAX_LEN
+            ),
+            Self::InProgress => write!(f, "the backend is already restarting"),
+            Self::NotOwned => write!(
+                f,
+                "the backend was not started by this launch; relaunch with {} instead",
+                PROFILE_ARG
+            ),
+            Self::SocketTransport => write!(
+                f,
+                "profiles cannot be switched in socket mode; relaunch with {} instead",
+                PROFILE_ARG
+            ),
+            Self::Insecure(problem) => write!(f, "{}", problem),
+            Self::OpenElsewhere(name) => {
+                write!(f, "profile '{}' is open in another Locus window", name)
+            }
+            Self::Io { path, source } => write!(f, "'{}': {}", path.display(), source),
+            Self::Restart { profile, message } => write!(
+                f,
+                "switched to profile '{}' but the backend did not restart: {}",
+                profile, message
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ProfileError {}
+
+#[derive(Debug, Serialize)]
+pub struct ProfileList {
+    active: String,
+    profiles: Vec<String>,
+}
+
+// Names become directory names, so nothing that could escape or collide on a
+// case-insensitive file system.
+fn validate_profile_name(name: &str) -> Result<(), ProfileError> {
+    let valid = !name.is_empty()
+        && name.len() <= PROFILE_NAME_MAX_LEN
+        && name
+            .chars()
+            .all(|ch| ch.is_ascii_lowercase() || ch.is_ascii_digit() || ch == '-' || ch == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(ProfileError::InvalidName(name.to_string()))
+    }
+}
+
+// "default" names the default profile explicitly; it maps to None like no flag at all.
+fn normalize_profile_name(name: &str) -> Result<Option<String>, ProfileError> {
+    let name = name.trim().to_ascii_lowercase();
+    validate_profile_name(&name)?;
+    Ok((name != DEFAULT_PROFILE).then_some(name))
+}
+
+fn parse_profile_arg(args: &[String]) -> Result<Option<String>, ProfileError> {
+    let mut profile = None;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if arg == PROFILE_ARG {
+            let name = args.next().ok_or(ProfileError::MissingName)?;
+            profile = normalize_profile_name(name)?;
+        } else if let Some(name) = arg.strip_prefix("--profile=") {
+            profile = normalize_profile_name(name)?;
+        }
+    }
+    Ok(profile)
+}
+
+pub fn init_from_args() -> Result<(), ProfileError> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let profile = parse_profile_arg(&args)?;
+    if let Some(name) = &profile {
+        info!(profile = %name, "using profile");
+    }
+    set_active_profile(profile);
+    Ok(())
+}
+
+pub fn active_profile() -> Option<String> {
+    match ACTIVE_PROFILE.read() {
+        Ok(guard) => guard.clone(),
+        Err(poisoned) => poisoned.into_inner().clone(),
+    }
+}
+
+fn set_active_profile(profile: Option<String>) {
+    if let Ok(mut guard) = ACTIVE_PROFILE.write() {
+        *guard = profile;
+    }
+}
+
+pub fn profile_data_dir(base_dir: &Path, name: &str) -> PathBuf {
+    base_dir.join(PROFILES_DIR_NAME).join(name)
+}
+
+// A profile exists once its directory does; `--profile <new name>` creates it.
+fn list_profile_names(base_dir: &Path) -> Vec<String> {
+    let mut names: Vec<String> = fs::read_dir(base_dir.join(PROFILES_DIR_NAME))
+        .map(|entries| {
+            entries
+                .filter_map(Result::ok)
+                .filter(|entry| entry.path().is_dir())
+                .filter_map(|entry| entry.file_name().into_string().ok())
+                .filter(|name| name != DEFAULT_PROFILE && validate_profile_name(name).is_ok())
+                .collect()
+        })
+        .unwrap_or_default();
+    names.sort();
+    names.insert(0, DEFAULT_PROFILE.to_string());
+    names
+}
+
+fn active_profile_name() -> String {
+    active_profile().unwrap_or_else(|| DEFAULT_PROFILE.to_string())
+}
+
+pub fn tray_submenu() -> SystemTraySubmenu {
+    let active = active_profile_name();
+    let menu = list_profile_names(&resolve_base_data_dir())
+        .into_iter()
+        .fold(SystemTrayMenu::new(), |menu, name| {
+            let mut item =
+                CustomMenuItem::new(format!("{}{}", TRAY_PROFILE_ITEM_PREFIX, name), &name);
+            item.selected = name == active;
+            menu.add_item(item)
+        })
+        .add_native_item(SystemTrayMenuItem::Separator)
+        .add_item(CustomMenuItem::new(
+            TRAY_NEW_PROFILE_ITEM_ID.to_string(),
+            "New profile…",
+        ));
+    SystemTraySubmenu::new("Profile", menu)
+}
+
+// Native dialogs cannot take text, so the main window asks for the name and then
+// calls switch_profile_command, which creates the profile.
+pub fn request_new_profile(app: &AppHandle) {
+    let Some(window) = app.get_window("main") else {
+        warn!("no main window to ask for a profile name yet");
+        return;
+    };
+    let _ = window.show();
+    let _ = window.unminimize();
+    let _ = window.set_focus();
+    let _ = window.emit(NEW_PROFILE_EVENT, ());
+}
+
+fn switch_owned_backend(
+    app: &AppHandle,
+    backend: &BackendState,
+    profile: Option<String>,
+) -> Result<(), ProfileError> {
+    let owned = backend
+        .child
+        .lock()
+        .map(|guard| guard.is_some())
+        .unwrap_or(false);
+    if cfg!(debug_assertions) || !owned {
+        return Err(ProfileError::NotOwned);
+    }
+    if backend_socket::unix_socket_path().is_some() {
+        return Err(ProfileError::SocketTransport);
+    }
+
+    let name = profile
+        .clone()
+        .unwrap_or_else(|| DEFAULT_PROFILE.to_string());
+    let data_dir = match &profile {
+        Some(name) => profile_data_dir(&resolve_base_data_dir(), name),
+        None => resolve_default_profile_dir(),
+    };
+    data_dir_permissions::create_private_dir(&data_dir).map_err(|source| ProfileError::Io {
+        path: data_dir.clone(),
+        source,
+    })?;
+    if let Some(problem) = data_dir_permissions::inspect(&data_dir) {
+        if problem.is_fatal() {
+            return Err(ProfileError::Insecure(problem));
+        }
+    }
+
+    let guard = single_instance::claim_data_dir(app, &data_dir)
+        .map_err(|source| ProfileError::Io {
+            path: data_dir.clone(),
+            source,
+        })?
+        .ok_or_else(|| ProfileError::OpenElsewhere(name.clone()))?;
+
+    info!(profile = %name, data_dir = %data_dir.display(), "switching profile");
+    stop_backend_child(backend);
+    set_locus_data_dir(data_dir);
+    set_active_profile(profile);
+    // Releases the previous profile, so it can be opened by another launch again.
+    app.state::<single_instance::ActiveInstance>()
+        .replace(guard);
+
+    restart_backend_child(backend).map_err(|message| ProfileError::Restart {
+        profile: name,
+        message,
+    })
+}
+
+pub fn switch_profile(app: &AppHandle, name: &str) -> Result<(), ProfileError> {
+    let profile = normalize_profile_name(name)?;
+    if profile == active_profile() {
+        return Ok(());
+    }
+
+    let backend: State<BackendState> = app.state();
+    if backend.restarting.swap(true, Ordering::SeqCst) {
+        return Err(ProfileError::InProgress);
+    }
+    let result = switch_owned_backend(app, &backend, profile);
+    backend.restarting.store(false, Ordering::SeqCst);
+
+    // A rebuild both moves the checkmark and lists a profile the switch just created.
+    rebuild_tray_menu(app);
+    if result.is_ok() || matches!(result, Err(ProfileError::Restart { .. })) {
+        let _ = app.emit_all(PROFILE_CHANGED_EVENT, active_profile_name());
+        if let Some(window) = app.get_window("main") {
+            let _ = window.eval("window.location.reload()");
+        }
+    }
+    result
+}
+
+#[tauri::command]
+pub fn list_profiles() -> ProfileList {
+    ProfileList {
+        active: active_profile_name(),
+        profiles: list_profile_names(&resolve_base_data_dir()),
+    }
+}
+
+#[tauri::command]
+pub async fn switch_profile_command(app: AppHandle, name: String) -> Result<(), String> {
+    tauri::async_runtime::spawn_blocking(move || switch_profile(&app, &name))
+        .await
+        .map_err(|err| err.to_string())?
+        .map_err(|err| err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(raw: &[&str]) -> Vec<String> {
+        raw.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn parses_profile_flag_forms() {
+        assert_eq!(parse_profile_arg(&args(&["/home/user"])).ok(), Some(None));
+        assert_eq!(
+            parse_profile_arg(&args(&["--profile", "Work"])).ok(),
+            Some(Some("work".to_string()))
+        );
+        assert_eq!(
+            parse_profile_arg(&args(&["--profile=client-a"])).ok(),
+            Some(Some("client-a".to_string()))
+        );
+        assert_eq!(
+            parse_profile_arg(&args(&["--profile", "default"])).ok(),
+            Some(None)
+        );
+        assert!(matches!(
+            parse_profile_arg(&args(&["--profile"])),
+            Err(ProfileError::MissingName)
+        ));
+        assert!(matches!(
+            parse_profile_arg(&args(&["--profile=../x"])),
+            Err(ProfileError::InvalidName(_))
+        ));
+    }
+}
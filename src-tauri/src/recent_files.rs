@@ -0,0 +1,362 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::atomic::Ordering;
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+
+use serde_json::{json, Value};
+use tauri::api::dialog::blocking::MessageDialogBuilder;
+use tauri::api::dialog::{MessageDialogButtons, MessageDialogKind};
+use tauri::api::notification::Notification;
+use tauri::{AppHandle, CustomMenuItem, Manager, State, SystemTrayMenu, SystemTraySubmenu};
+use tracing::{info, warn};
+
+use crate::backend_socket::request_backend_json;
+use crate::{rebuild_tray_menu, BackendState};
+
+pub const TRAY_RESTORE_ITEM_PREFIX: &str = "restore:";
+pub const FILE_RESTORED_EVENT: &str = "locus://file-restored";
+
+const TRAY_PLACEHOLDER_ITEM_ID: &str = "recent_files_placeholder";
+const RECENT_FILES_LIMIT: usize = 8;
+const RECENT_VERSIONS_LIMIT: usize = 5;
+// Several events usually belong to one save, so scan well past the files shown.
+const EVENT_SCAN_LIMIT: usize = 200;
+// Each candidate costs a /files/versions request.
+const VERSION_LOOKUP_LIMIT: usize = 3 * RECENT_FILES_LIMIT;
+const RECENT_FILES_REFRESH_INTERVAL_MS: u64 = 15000;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct RecentVersion {
+    id: i64,
+    number: i64,
+    created_at: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct RecentFile {
+    path: String,
+    versions: Vec<RecentVersion>,
+}
+
+// None until the backend answers, and again while it is locked or unreachable.
+#[derive(Default)]
+pub struct RecentFilesState {
+    current: Mutex<Option<Vec<RecentFile>>>,
+}
+
+fn encode_query_value(raw: &str) -> String {
+    raw.bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+// Newest first, one entry per file; a move counts as a change to where the file went.
+fn changed_paths(events: &Value) -> Vec<String> {
+    let mut paths: Vec<String> = Vec::new();
+    for event in events.as_array().into_iter().flatten() {
+        let moved_to = match event.get("event_type").and_then(Value::as_str) {
+            Some("moved") => event.get("dest_path").and_then(Value::as_str),
+            _ => None,
+        };
+        let Some(path) = moved_to.or_else(|| event.get("src_path").and_then(Value::as_str)) else {
+            continue;
+        };
+        if !paths.iter().any(|known| known == path) {
+            paths.push(path.to_string());
+        }
+    }
+    paths
+}
+
+fn parse_versions(body: &Value) -> Vec<RecentVersion> {
+    body.as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|version| {
+            Some(RecentVersion {
+                id: version.get("id")?.as_i64()?,
+                number: version.get("version_number")?.as_i64()?,
+                created_at: version
+                    .get("created_at")
+                    .and_then(Value::as_str)
+                    .map(str::to_string),
+            })
+        })
+        .take(RECENT_VERSIONS_LIMIT)
+        .collect()
+}
+
+fn version_label(version: &RecentVersion) -> String {
+    // "2026-10-15T14:02:11.52" -> "2026-10-15 14:02"
+    match version.created_at.as_deref().and_then(|raw| raw.get(..16)) {
+        Some(minute) => format!("Version {} · {}", version.number, minute.replace('T', " ")),
+        None => format!("Version {}", version.number),
+    }
+}
+
+fn file_name(path: &str) -> String {
+    Path::new(path)
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.to_string())
+}
+
+fn file_titles(files: &[RecentFile]) -> Vec<String> {
+    let mut name_counts: HashMap<String, usize> = HashMap::new();
+    for file in files {
+        *name_counts.entry(file_name(&file.path)).or_default() += 1;
+    }
+    files
+        .iter()
+        .map(|file| {
+            let name = file_name(&file.path);
+            let folder = Path::new(&file.path)
+                .parent()
+                .and_then(Path::file_name)
+                .map(|folder| folder.to_string_lossy().into_owned());
+            match folder {
+                Some(folder) if name_counts[&name] > 1 => format!("{} ({})", name, folder),
+                _ => name,
+            }
+        })
+        .collect()
+}
+
+fn current_files(state: &RecentFilesState) -> Option<Vec<RecentFile>> {
+    state.current.lock().ok().and_then(|files| files.clone())
+}
+
+pub fn tray_submenu(state: &RecentFilesState) -> SystemTraySubmenu {
+    let placeholder = |title: &str| {
+        SystemTrayMenu::new()
+            .add_item(CustomMenuItem::new(TRAY_PLACEHOLDER_ITEM_ID.to_string(), title).disabled())
+    };
+    let menu = match current_files(state) {
+        None => placeholder("Not available right now"),
+        Some(files) if files.is_empty() => placeholder("No recent changes"),
+        Some(files) => files.iter().zip(file_titles(&files)).fold(
+            SystemTrayMenu::new(),
+            |menu, (file, title)| {
+                let versions = file
+                    .versions
+                    .iter()
+                    .fold(SystemTrayMenu::new(), |menu, version| {
+                        menu.add_item(CustomMenuItem::new(
+                            format!("{}{}", TRAY_RESTORE_ITEM_PREFIX, version.id),
+                            version_label(version),
+                        ))
+                    });
+                menu.add_submenu(SystemTraySubmenu::new(title, versions))
+            },
+        ),
+    };
+    SystemTraySubmenu::new("Recent files", menu)
+}
+
+fn fetch_recent_files(port: u16) -> Option<Vec<RecentFile>> {
+    let events = request_backend_json(
+        port,
+        "GET",
+        &format!("/files/events?limit={}", EVENT_SCAN_LIMIT),
+        None,
+    )
+    .ok()?;
+
+    let mut files = Vec::new();
+    for path in changed_paths(&events)
+        .into_iter()
+        .take(VERSION_LOOKUP_LIMIT)
+    {
+        if files.len() == RECENT_FILES_LIMIT {
+            break;
+        }
+        let target = format!(
+            "/files/versions?path={}&create=false",
+            encode_query_value(&path)
+        );
+        // Read-only; paths outside the watched folders (403) or without backups drop out.
+        let Ok(body) = request_backend_json(port, "GET", &target, None) else {
+            continue;
+        };
+        let versions = parse_versions(&body);
+        if !versions.is_empty() {
+            files.push(RecentFile { path, versions });
+        }
+    }
+    Some(files)
+}
+
+pub fn start_recent_files_monitor(app: AppHandle) {
+    thread::spawn(move || loop {
+        let backend: State<BackendState> = app.state();
+        if backend.shutting_down.load(Ordering::SeqCst) {
+            return;
+        }
+
+        let latest = fetch_recent_files(backend.port.load(Ordering::SeqCst));
+        let state: State<RecentFilesState> = app.state();
+        let changed = match state.current.lock() {
+            Ok(mut current) if *current != latest => {
+                *current = latest;
+                true
+            }
+            _ => false,
+        };
+        // Only on change: rebuilding closes the menu if it happens to be open.
+        if changed {
+            rebuild_tray_menu(&app);
+        }
+
+        thread::sleep(Duration::from_millis(RECENT_FILES_REFRESH_INTERVAL_MS));
+    });
+}
+
+fn notify(app: &AppHandle, title: &str, body: &str, kind: MessageDialogKind) {
+    let shown = Notification::new(&app.config().tauri.bundle.identifier)
+        .title(title)
+        .body(body)
+        .show();
+    if let Err(err) = shown {
+        warn!(error = %err, "desktop notification failed; showing a dialog instead");
+        MessageDialogBuilder::new(title, body).kind(kind).show();
+    }
+}
+
+fn find_version(state: &RecentFilesState, version_id: i64) -> Option<(String, RecentVersion)> {
+    current_files(state)?.into_iter().find_map(|file| {
+        let version = file
+            .versions
+            .into_iter()
+            .find(|version| version.id == version_id)?;
+        Some((file.path, version))
+    })
+}
+
+pub fn restore_from_tray(app: &AppHandle, item_id: &str) {
+    let Some(version_id) = item_id
+        .strip_prefix(TRAY_RESTORE_ITEM_PREFIX)
+        .and_then(|raw| raw.parse::<i64>().ok())
+    else {
+        return;
+    };
+    let Some((path, version)) = find_version(&app.state::<RecentFilesState>(), version_id) else {
+        warn!(
+            version_id,
+            "tray restore item no longer matches a recent file"
+        );
+        return;
+    };
+
+    let confirmed = MessageDialogBuilder::new(
+        "Restore file",
+        format!(
+            "Replace the current contents of\n{}\nwith {}?",
+            path,
+            version_label(&version).to_lowercase()
+        ),
+    )
+    .kind(MessageDialogKind::Warning)
+    .buttons(MessageDialogButtons::OkCancelWithLabels(
+        String::from("Restore"),
+        String::from("Cancel"),
+    ))
+    .show();
+    if !confirmed {
+        return;
+    }
+
+    let port = app.state::<BackendState>().port.load(Ordering::SeqCst);
+    let name = file_name(&path);
+    match request_backend_json(
+        port,
+        "POST",
+        "/files/restore",
+        Some(json!({ "version_id": version_id })),
+    ) {
+        Ok(_) => {
+            info!(path = %path, version = version.number, "restored file from the tray");
+            let _ = app.emit_all(
+                FILE_RESTORED_EVENT,
+                json!({ "path": path, "version": version.number }),
+            );
+            notify(
+                app,
+                "File restored",
+                &format!("{} is back at version {}", name, version.number),
+                MessageDialogKind::Info,
+            );
+        }
+        Err(err) => {
+            warn!(path = %path, version = version.number, error = %err, "tray restore failed");
+            notify(
+                app,
+                "Restore failed",
+                &format!("{} was not restored: {}", name, err),
+                MessageDialogKind::Error,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lists_each_changed_file_once_with_its_versions() {
+        let events = json!([
+            { "event_type": "modified", "src_path": "/home/u/docs/report.txt", "dest_path": null },
+            { "event_type": "moved", "src_path": "/home/u/notes/a.md", "dest_path": "/home/u/notes/b.md" },
+            { "event_type": "created", "src_path": "/home/u/docs/report.txt", "dest_path": null },
+            { "event_type": "modified", "src_path": "/home/u/notes/report.txt", "dest_path": null }
+        ]);
+        assert_eq!(
+            changed_paths(&events),
+            vec![
+                "/home/u/docs/report.txt",
+                "/home/u/notes/b.md",
+                "/home/u/notes/report.txt"
+            ]
+        );
+
+        let versions = parse_versions(&json!([
+            { "id": 42, "version_number": 3, "created_at": "2026-10-15T14:02:11.520000" },
+            { "id": 17, "version_number": 2, "created_at": null },
+            { "id": 9, "version_number": 1 }
+        ]));
+        assert_eq!(versions.len(), 3);
+        assert_eq!(version_label(&versions[0]), "Version 3 · 2026-10-15 14:02");
+        assert_eq!(version_label(&versions[1]), "Version 2");
+
+        let files: Vec<RecentFile> = changed_paths(&events)
+            .into_iter()
+            .map(|path| RecentFile {
+                path,
+                versions: versions.clone(),
+            })
+            .collect();
+        assert_eq!(
+            file_titles(&files),
+            vec!["report.txt (docs)", "b.md", "report.txt (notes)"]
+        );
+        assert_eq!(
+            encode_query_value("/home/u/My Docs/ü&x.txt"),
+            "%2Fhome%2Fu%2FMy%20Docs%2F%C3%BC%26x.txt"
+        );
+
+        let state = RecentFilesState::default();
+        assert_eq!(find_version(&state, 17), None);
+        *state.current.lock().expect("recent files lock") = Some(files);
+        assert_eq!(
+            find_version(&state, 17).map(|(path, version)| (path, version.number)),
+            Some(("/home/u/docs/report.txt".to_string(), 2))
+        );
+    }
+}
@@ -0,0 +1,605 @@
+use std::fmt;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Read, Write};
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::Ordering;
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tauri::{AppHandle, Manager, State};
+use tracing::{error, info, warn};
+
+use crate::{
+    backend_socket, config, data_dir_permissions, profiles, resolve_base_data_dir,
+    resolve_locus_data_dir, restart_backend_child, set_locus_data_dir, single_instance,
+    stop_backend_child, BackendState,
+};
+
+pub const RELOCATE_PROGRESS_EVENT: &str = "locus://relocate-progress";
+
+const DATA_POINTER_FILE_NAME: &str = "data-dir.json";
+const COPY_BUFFER_BYTES: usize = 256 * 1024;
+
+// Top-level entries that belong to this launch or to the base dir, not to the data.
+const SKIPPED_ENTRIES: &[&str] = &[
+    "locus.lock",
+    "run",
+    "instance.port",
+    "backend.json",
+    "profiles",
+    config::CONFIG_FILE_NAME,
+    DATA_POINTER_FILE_NAME,
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum RelocationPhase {
+    Stopping,
+    Copying,
+    Restarting,
+    Done,
+    Failed,
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct RelocationProgress {
+    phase: RelocationPhase,
+    copied_files: usize,
+    total_files: usize,
+    copied_bytes: u64,
+    total_bytes: u64,
+    current: Option<String>,
+    message: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RelocationReport {
+    previous_dir: PathBuf,
+    data_dir: PathBuf,
+    copied_files: usize,
+    copied_bytes: u64,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct DataDirPointer {
+    data_dir: PathBuf,
+}
+
+#[derive(Debug)]
+enum RelocationError {
+    InProgress,
+    PinnedByEnv,
+    PinnedByConfig(PathBuf),
+    NamedProfile(String),
+    NotOwned,
+    SocketTransport,
+    NotAbsolute(PathBuf),
+    ParentComponent(PathBuf),
+    Overlapping(PathBuf),
+    TargetNotEmpty(PathBuf),
+    Io { path: PathBuf, source: io::Error },
+    VerificationFailed(PathBuf),
+    Restart { data_dir: PathBuf, message: String },
+}
+
+impl fmt::Display for RelocationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InProgress => write!(f, "the backend is already restarting"),
+            Self::PinnedByEnv => write!(
+                f,
+                "the data directory is pinned by LOCUS_DATA_DIR; change the variable instead"
+            ),
+            Self::PinnedByConfig(path) => write!(
+                f,
+                "the data directory is pinned by paths.data_dir in '{}'; change it there instead",
+                path.display()
+            ),
+            Self::NamedProfile(name) => write!(
+                f,
+                "only the default profile can be relocated, '{}' is active",
+                name
+            ),
+            Self::NotOwned => write!(
+                f,
+                "the backend was not started by this launch, so it cannot be moved from here"
+            ),
+            Self::SocketTransport => write!(
+                f,
+                "the data directory cannot be moved in socket mode; quit and relocate with the TCP transport"
+            ),
+            Self::NotAbsolute(path) => {
+                write!(f, "'{}' is not an absolute path", path.display())
+            }
+            Self::ParentComponent(path) => {
+                write!(f, "'{}' must not contain '..'", path.display())
+            }
+            Self::Overlapping(path) => write!(
+                f,
+                "'{}' overlaps the current data directory",
+                path.display()
+            ),
+            Self::TargetNotEmpty(path) => {
+                write!(
+                    f,
+                    "'{}' exists and is not an empty directory",
+                    path.display()
+                )
+            }
+            Self::Io { path, source } => write!(f, "'{}': {}", path.display(), source),
+            Self::VerificationFailed(path) => {
+                write!(
+                    f,
+                    "copy of '{}' does not match the original",
+                    path.display()
+                )
+            }
+            Self::Restart { data_dir, message } => write!(
+                f,
+                "data moved to '{}' but the backend did not restart there yet, the app keeps retrying: {}",
+                data_dir.display(),
+                message
+            ),
+        }
+    }
+}
+
+impl std::error::Error for RelocationError {}
+
+fn io_error(path: &Path) -> impl FnOnce(io::Error) -> RelocationError + '_ {
+    move |source| RelocationError::Io {
+        path: path.to_path_buf(),
+        source,
+    }
+}
+
+pub fn read_data_dir_pointer(base_dir: &Path) -> Option<PathBuf> {
+    let path = base_dir.join(DATA_POINTER_FILE_NAME);
+    let raw = fs::read(&path).ok()?;
+    match serde_json::from_slice::<DataDirPointer>(&raw) {
+        Ok(pointer) if pointer.data_dir.is_absolute() => Some(pointer.data_dir),
+        _ => {
+            warn!(path = %path.display(), "ignoring malformed data dir pointer");
+            None
+        }
+    }
+}
+
+// Renamed into place so a crash never leaves a half-written pointer behind.
+fn write_data_dir_pointer(base_dir: &Path, data_dir: &Path) -> io::Result<()> {
+    fs::create_dir_all(base_dir)?;
+    let path = base_dir.join(DATA_POINTER_FILE_NAME);
+    let staging = base_dir.join(format!("{}.tmp", DATA_POINTER_FILE_NAME));
+    let body = serde_json::to_vec_pretty(&DataDirPointer {
+        data_dir: data_dir.to_path_buf(),
+    })?;
+    let mut file = File::create(&staging)?;
+    file.write_all(&body)?;
+    file.sync_all()?;
+    fs::rename(&staging, &path)
+}
+
+// Canonicalizes the deepest existing ancestor, so targets that do not exist yet still
+// compare correctly against a symlinked data dir.
+fn normalize(path: &Path) -> PathBuf {
+    let mut existing = path;
+    let mut rest = Vec::new();
+    loop {
+        if let Ok(mut canonical) = fs::canonicalize(existing) {
+            for part in rest.iter().rev() {
+                canonical.push(part);
+            }
+            return canonical;
+        }
+        match (existing.parent(), existing.file_name()) {
+            (Some(parent), Some(name)) => {
+                rest.push(name);
+                existing = parent;
+            }
+            _ => return path.to_path_buf(),
+        }
+    }
+}
+
+fn validate_target(current: &Path, target: &Path) -> Result<(), RelocationError> {
+    if !target.is_absolute() {
+        return Err(RelocationError::NotAbsolute(target.to_path_buf()));
+    }
+    if target.components().any(|part| part == Component::ParentDir) {
+        return Err(RelocationError::ParentComponent(target.to_path_buf()));
+    }
+    let normalized_current = normalize(current);
+    let normalized_target = normalize(target);
+    if normalized_target.starts_with(&normalized_current)
+        || normalized_current.starts_with(&normalized_target)
+    {
+        return Err(RelocationError::Overlapping(target.to_path_buf()));
+    }
+    if target.exists() && !target.is_dir() {
+        return Err(RelocationError::TargetNotEmpty(target.to_path_buf()));
+    }
+    match fs::read_dir(target) {
+        Ok(mut entries) => match entries.next() {
+            None => Ok(()),
+            Some(_) => Err(RelocationError::TargetNotEmpty(target.to_path_buf())),
+        },
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(()),
+        Err(source) => Err(RelocationError::Io {
+            path: target.to_path_buf(),
+            source,
+        }),
+    }
+}
+
+enum EntryKind {
+    Dir,
+    File(u64),
+    Symlink,
+}
+
+struct PlannedEntry {
+    relative: PathBuf,
+    kind: EntryKind,
+}
+
+fn plan_copy(source: &Path) -> Result<Vec<PlannedEntry>, RelocationError> {
+    let mut planned = Vec::new();
+    let mut pending = vec![PathBuf::new()];
+    while let Some(relative) = pending.pop() {
+        let dir = source.join(&relative);
+        let mut entries = fs::read_dir(&dir)
+            .map_err(io_error(&dir))?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(io_error(&dir))?;
+        entries.sort_by_key(|entry| entry.file_name());
+
+        for entry in entries {
+            let name = entry.file_name();
+            if relative.as_os_str().is_empty()
+                && SKIPPED_ENTRIES.iter().any(|skipped| name == *skipped)
+            {
+                continue;
+            }
+            let child = relative.join(&name);
+            let file_type = entry.file_type().map_err(io_error(&entry.path()))?;
+            let kind = if file_type.is_symlink() {
+                EntryKind::Symlink
+            } else if file_type.is_dir() {
+                pending.push(child.clone());
+                EntryKind::Dir
+            } else if file_type.is_file() {
+                let metadata = entry.metadata().map_err(io_error(&entry.path()))?;
+                EntryKind::File(metadata.len())
+            } else {
+                continue;
+            };
+            planned.push(PlannedEntry {
+                relative: child,
+                kind,
+            });
+        }
+    }
+    Ok(planned)
+}
+
+fn hash_reader(
+    mut reader: impl Read,
+    mut sink: impl FnMut(&[u8]) -> io::Result<()>,
+) -> io::Result<(u64, Vec<u8>)> {
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; COPY_BUFFER_BYTES];
+    let mut total = 0u64;
+    loop {
+        let read = reader.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+        sink(&buffer[..read])?;
+        total += read as u64;
+    }
+    Ok((total, hasher.finalize().to_vec()))
+}
+
+fn copy_verified(source: &Path, target: &Path) -> Result<u64, RelocationError> {
+    let input = File::open(source).map_err(io_error(source))?;
+    let mut output = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(target)
+        .map_err(io_error(target))?;
+    let (written, source_digest) =
+        hash_reader(input, |chunk| output.write_all(chunk)).map_err(io_error(source))?;
+    output.sync_all().map_err(io_error(target))?;
+    drop(output);
+
+    let permissions = fs::metadata(source)
+        .map_err(io_error(source))?
+        .permissions();
+    fs::set_permissions(target, permissions).map_err(io_error(target))?;
+
+    let reread = File::open(target).map_err(io_error(target))?;
+    let (read_back, target_digest) = hash_reader(reread, |_| Ok(())).map_err(io_error(target))?;
+    if read_back != written || target_digest != source_digest {
+        return Err(RelocationError::VerificationFailed(source.to_path_buf()));
+    }
+    Ok(written)
+}
+
+#[cfg(unix)]
+fn copy_symlink(source: &Path, target: &Path) -> io::Result<()> {
+    std::os::unix::fs::symlink(fs::read_link(source)?, target)
+}
+
+#[cfg(not(unix))]
+fn copy_symlink(source: &Path, _target: &Path) -> io::Result<()> {
+    warn!(path = %source.display(), "not relocating symbolic link");
+    Ok(())
+}
+
+fn copy_data_dir(
+    source: &Path,
+    target: &Path,
+    on_progress: &mut dyn FnMut(RelocationProgress),
+) -> Result<(usize, u64), RelocationError> {
+    let planned = plan_copy(source)?;
+    let total_files = planned
+        .iter()
+        .filter(|entry| matches!(entry.kind, EntryKind::File(_)))
+        .count();
+    let total_bytes = planned
+        .iter()
+        .map(|entry| match entry.kind {
+            EntryKind::File(len) => len,
+            _ => 0,
+        })
+        .sum();
+
+    data_dir_permissions::create_private_dir(target).map_err(io_error(target))?;
+    let mut copied_files = 0;
+    let mut copied_bytes = 0;
+    for entry in &planned {
+        let from = source.join(&entry.relative);
+        let to = target.join(&entry.relative);
+        match entry.kind {
+            EntryKind::Dir => fs::create_dir(&to).map_err(io_error(&to))?,
+            EntryKind::Symlink => copy_symlink(&from, &to).map_err(io_error(&to))?,
+            EntryKind::File(_) => {
+                on_progress(RelocationProgress {
+                    phase: RelocationPhase::Copying,
+                    copied_files,
+                    total_files,
+                    copied_bytes,
+                    total_bytes,
+                    current: Some(entry.relative.display().to_string()),
+                    message: None,
+                });
+                copied_bytes += copy_verified(&from, &to)?;
+                copied_files += 1;
+            }
+        }
+    }
+    Ok((copied_files, copied_bytes))
+}
+
+fn emit_progress(app: &AppHandle, phase: RelocationPhase, message: Option<String>) {
+    let payload = RelocationProgress {
+        phase,
+        copied_files: 0,
+        total_files: 0,
+        copied_bytes: 0,
+        total_bytes: 0,
+        current: None,
+        message,
+    };
+    let _ = app.emit_all(RELOCATE_PROGRESS_EVENT, payload);
+}
+
+fn relocate_owned_backend(
+    app: &AppHandle,
+    backend: &BackendState,
+    target: PathBuf,
+) -> Result<RelocationReport, RelocationError> {
+    let explicit = std::env::var("LOCUS_DATA_DIR")
+        .map(|value| !value.trim().is_empty())
+        .unwrap_or(false);
+    if explicit {
+        return Err(RelocationError::PinnedByEnv);
+    }
+    if config::shell_config().paths.data_dir.is_some() {
+        return Err(RelocationError::PinnedByConfig(config::config_path()));
+    }
+    if let Some(name) = profiles::active_profile() {
+        return Err(RelocationError::NamedProfile(name));
+    }
+    // Dev builds run backend/service_entry.py, which a sidecar restart cannot replace.
+    let owned = backend
+        .child
+        .lock()
+        .map(|guard| guard.is_some())
+        .unwrap_or(false);
+    if cfg!(debug_assertions) || !owned {
+        return Err(RelocationError::NotOwned);
+    }
+    if backend_socket::unix_socket_path().is_some() {
+        return Err(RelocationError::SocketTransport);
+    }
+
+    let current = resolve_locus_data_dir();
+    validate_target(&current, &target)?;
+    let target_existed = target.exists();
+    // An existing (empty) target keeps whatever mode it had unless restricted here.
+    if target_existed {
+        data_dir_permissions::repair(&target).map_err(io_error(&target))?;
+    }
+
+    info!(
+        from = %current.display(),
+        to = %target.display(),
+        "relocating data"
+    );
+    emit_progress(app, RelocationPhase::Stopping, None);
+    stop_backend_child(backend);
+
+    let mut on_progress = |progress: RelocationProgress| {
+        let _ = app.emit_all(RELOCATE_PROGRESS_EVENT, progress);
+    };
+    let base_dir = resolve_base_data_dir();
+    let copied = copy_data_dir(&current, &target, &mut on_progress).and_then(|copied| {
+        write_data_dir_pointer(&base_dir, &target).map_err(io_error(&base_dir))?;
+        Ok(copied)
+    });
+    let (copied_files, copied_bytes) = match copied {
+        Ok(copied) => copied,
+        Err(err) => {
+            // Nothing points at the partial copy, so the old dir is still authoritative.
+            let _ = fs::remove_dir_all(&target);
+            if target_existed {
+                let _ = fs::create_dir(&target);
+            }
+            emit_progress(app, RelocationPhase::Restarting, None);
+            if let Err(restart_err) = restart_backend_child(backend) {
+                error!(
+                    error = %restart_err,
+                    "backend did not restart after a failed relocation"
+                );
+            }
+            return Err(err);
+        }
+    };
+    set_locus_data_dir(target.clone());
+
+    // A second launch now resolves the new dir, so it has to find that one locked too.
+    match single_instance::claim_data_dir(app, &target) {
+        Ok(Some(guard)) => app
+            .state::<single_instance::ActiveInstance>()
+            .replace(guard),
+        Ok(None) => warn!("another Locus instance already holds the new data dir"),
+        Err(err) => warn!(error = %err, "single-instance lock unavailable"),
+    }
+
+    // The copy is verified and the pointer stays on it; if this spawn fails, the
+    // supervisor keeps retrying in the new dir once the restart flag clears.
+    emit_progress(app, RelocationPhase::Restarting, None);
+    restart_backend_child(backend).map_err(|message| RelocationError::Restart {
+        data_dir: target.clone(),
+        message,
+    })?;
+
+    info!(
+        files = copied_files,
+        bytes = copied_bytes,
+        previous = %current.display(),
+        "relocated data; the old data dir is left in place"
+    );
+    Ok(RelocationReport {
+        previous_dir: current,
+        data_dir: target,
+        copied_files,
+        copied_bytes,
+    })
+}
+
+fn relocate(app: &AppHandle, target: PathBuf) -> Result<RelocationReport, RelocationError> {
+    let backend: State<BackendState> = app.state();
+    // Claimed before looking at the child, so the supervisor cannot restart it under us.
+    if backend.restarting.swap(true, Ordering::SeqCst) {
+        return Err(RelocationError::InProgress);
+    }
+    let result = relocate_owned_backend(app, &backend, target);
+    backend.restarting.store(false, Ordering::SeqCst);
+
+    match &result {
+        Ok(_) => emit_progress(app, RelocationPhase::Done, None),
+        Err(err) => {
+            error!(error = %err, "data relocation failed");
+            emit_progress(app, RelocationPhase::Failed, Some(err.to_string()));
+        }
+    }
+    result
+}
+
+#[tauri::command]
+pub async fn relocate_data_dir(app: AppHandle, target: String) -> Result<RelocationReport, String> {
+    let target = PathBuf::from(target.trim());
+    tauri::async_runtime::spawn_blocking(move || relocate(&app, target))
+        .await
+        .map_err(|err| err.to_string())?
+        .map_err(|err| err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::scratch_dir;
+
+    #[test]
+    fn copies_data_but_not_launch_state() {
+        let root = scratch_dir("relocate");
+        let source = root.join("old");
+        let target = root.join("new");
+        fs::create_dir_all(source.join("storage").join("ab")).expect("mkdir failed");
+        fs::create_dir_all(source.join("run")).expect("mkdir failed");
+        fs::write(source.join("locus.db"), b"sqlite").expect("write failed");
+        fs::write(
+            source.join("storage").join("ab").join("cdef"),
+            vec![7u8; 300_000],
+        )
+        .expect("write failed");
+        fs::write(source.join("locus.lock"), b"").expect("write failed");
+        fs::write(source.join(DATA_POINTER_FILE_NAME), b"{}").expect("write failed");
+
+        let mut events = Vec::new();
+        let (files, bytes) = copy_data_dir(&source, &target, &mut |progress| events.push(progress))
+            .expect("copy failed");
+
+        assert_eq!((files, bytes), (2, 300_006));
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[1].total_bytes, 300_006);
+        assert_eq!(
+            fs::read(target.join("storage").join("ab").join("cdef")).expect("read failed"),
+            vec![7u8; 300_000]
+        );
+        assert!(!target.join("run").exists());
+        assert!(!target.join("locus.lock").exists());
+        assert!(!target.join(DATA_POINTER_FILE_NAME).exists());
+
+        write_data_dir_pointer(&source, &target).expect("pointer write failed");
+        assert_eq!(read_data_dir_pointer(&source), Some(target.clone()));
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn rejects_overlapping_or_occupied_targets() {
+        let root = scratch_dir("relocate-target");
+        let current = root.join("data");
+        fs::create_dir_all(&current).expect("mkdir failed");
+        fs::create_dir_all(root.join("busy")).expect("mkdir failed");
+        fs::write(root.join("busy").join("file"), b"x").expect("write failed");
+
+        assert!(matches!(
+            validate_target(&current, &current.join("nested")),
+            Err(RelocationError::Overlapping(_))
+        ));
+        assert!(matches!(
+            validate_target(&current, &root),
+            Err(RelocationError::Overlapping(_))
+        ));
+        assert!(matches!(
+            validate_target(&current, &root.join("busy")),
+            Err(RelocationError::TargetNotEmpty(_))
+        ));
+        assert!(matches!(
+            validate_target(&current, Path::new("relative")),
+            Err(RelocationError::NotAbsolute(_))
+        ));
+        assert!(matches!(
+            validate_target(&current, &root.join("..").join("elsewhere")),
+            Err(RelocationError::ParentComponent(_))
+        ));
+        assert!(validate_target(&current, &root.join("fresh")).is_ok());
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
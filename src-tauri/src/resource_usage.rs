@@ -0,0 +1,391 @@
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+use serde::Serialize;
+use tauri::State;
+
+#[cfg(target_os = "linux")]
+use std::collections::{HashMap, HashSet};
+#[cfg(target_os = "linux")]
+use std::fs;
+#[cfg(target_os = "linux")]
+use std::sync::atomic::Ordering;
+#[cfg(target_os = "linux")]
+use std::thread;
+#[cfg(target_os = "linux")]
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+#[cfg(target_os = "linux")]
+use tauri::{AppHandle, Manager};
+
+#[cfg(target_os = "linux")]
+use crate::BackendState;
+
+pub const RESOURCE_USAGE_EVENT: &str = "locus://resource-usage";
+
+#[cfg(target_os = "linux")]
+const RESOURCE_SAMPLE_INTERVAL_MS: u64 = 2000;
+// Five minutes of history at the default interval.
+const RESOURCE_HISTORY_LEN: usize = 150;
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct ResourceUsage {
+    pub process_count: usize,
+    // Percent of one core, so a busy multi-threaded backend can exceed 100.
+    pub cpu_percent: f64,
+    pub rss_bytes: u64,
+    pub read_bytes_per_sec: u64,
+    pub write_bytes_per_sec: u64,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct ResourceSample {
+    pub timestamp_ms: u64,
+    pub shell: ResourceUsage,
+    pub webview: ResourceUsage,
+    pub backend: ResourceUsage,
+    pub total: ResourceUsage,
+}
+
+#[derive(Default)]
+pub struct ResourceUsageState {
+    history: Mutex<VecDeque<ResourceSample>>,
+}
+
+// Oldest first, so the dashboard can chart it as is. Empty off Linux.
+#[tauri::command]
+pub fn get_resource_usage(
+    state: State<ResourceUsageState>,
+    limit: Option<usize>,
+) -> Vec<ResourceSample> {
+    let Ok(history) = state.history.lock() else {
+        return Vec::new();
+    };
+    let limit = limit.unwrap_or(RESOURCE_HISTORY_LEN).min(history.len());
+    history
+        .iter()
+        .skip(history.len() - limit)
+        .cloned()
+        .collect()
+}
+
+#[cfg(target_os = "linux")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct ProcStat {
+    pid: u32,
+    ppid: u32,
+    pgrp: u32,
+    cpu_ticks: u64,
+    rss_pages: u64,
+}
+
+// The comm field is parenthesised and may itself contain spaces or parentheses, so
+// split on the last ')'.
+#[cfg(target_os = "linux")]
+fn parse_proc_stat(raw: &str) -> Option<ProcStat> {
+    let (head, rest) = raw.rsplit_once(')')?;
+    let pid = head.split_whitespace().next()?.parse().ok()?;
+    let fields: Vec<&str> = rest.split_whitespace().collect();
+    let field = |index: usize| {
+        fields
+            .get(index)
+            .and_then(|value| value.parse::<u64>().ok())
+    };
+
+    Some(ProcStat {
+        pid,
+        ppid: field(1)? as u32,
+        pgrp: field(2)? as u32,
+        cpu_ticks: field(11)? + field(12)?,
+        rss_pages: field(21)?,
+    })
+}
+
+// Storage-level bytes; unreadable for other users' processes, which we never ask about.
+#[cfg(target_os = "linux")]
+fn read_proc_io(pid: u32) -> (u64, u64) {
+    let raw = fs::read_to_string(format!("/proc/{}/io", pid)).unwrap_or_default();
+    let value = |key: &str| {
+        raw.lines()
+            .find_map(|line| line.strip_prefix(key))
+            .and_then(|value| value.trim().parse::<u64>().ok())
+            .unwrap_or(0)
+    };
+    (value("read_bytes:"), value("write_bytes:"))
+}
+
+#[cfg(target_os = "linux")]
+fn read_process_table() -> Vec<ProcStat> {
+    let Ok(entries) = fs::read_dir("/proc") else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter_map(|entry| entry.file_name().to_str()?.parse::<u32>().ok())
+        .filter_map(|pid| parse_proc_stat(&fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?))
+        .collect()
+}
+
+// Walks parent links over a full /proc snapshot rather than the per-task children files,
+// which only list the main thread's children.
+#[cfg(target_os = "linux")]
+fn descendants(table: &[ProcStat], root: u32) -> HashSet<u32> {
+    let mut found = HashSet::from([root]);
+    let mut grew = true;
+    while grew {
+        grew = false;
+        for stat in table {
+            if !found.contains(&stat.pid) && found.contains(&stat.ppid) {
+                found.insert(stat.pid);
+                grew = true;
+            }
+        }
+    }
+    found.retain(|pid| table.iter().any(|stat| stat.pid == *pid));
+    found
+}
+
+// The backend is its process group (which also catches helpers reparented to init)
+// plus anything it spawned into another group; the webview is the rest of our tree.
+#[cfg(target_os = "linux")]
+fn classify(
+    table: &[ProcStat],
+    shell_pid: u32,
+    backend_pid: Option<u32>,
+) -> (Vec<u32>, Vec<u32>, Vec<u32>) {
+    let backend: HashSet<u32> = match backend_pid {
+        Some(backend_pid) => {
+            let mut backend = descendants(table, backend_pid);
+            backend.extend(
+                table
+                    .iter()
+                    .filter(|stat| stat.pgrp == backend_pid)
+                    .map(|stat| stat.pid),
+            );
+            backend
+        }
+        None => HashSet::new(),
+    };
+    let webview = descendants(table, shell_pid)
+        .into_iter()
+        .filter(|pid| *pid != shell_pid && !backend.contains(pid))
+        .collect();
+    (vec![shell_pid], webview, backend.into_iter().collect())
+}
+
+#[cfg(target_os = "linux")]
+#[derive(Clone, Copy, Default)]
+struct Counters {
+    cpu_ticks: u64,
+    read_bytes: u64,
+    write_bytes: u64,
+}
+
+#[cfg(target_os = "linux")]
+struct Sampler {
+    ticks_per_sec: f64,
+    page_size: u64,
+    previous: HashMap<u32, Counters>,
+    previous_at: Option<Instant>,
+}
+
+#[cfg(target_os = "linux")]
+impl Sampler {
+    fn new() -> Self {
+        // SAFETY: sysconf only reads static system configuration.
+        let (ticks, page_size) = unsafe {
+            (
+                libc::sysconf(libc::_SC_CLK_TCK),
+                libc::sysconf(libc::_SC_PAGESIZE),
+            )
+        };
+        Self {
+            ticks_per_sec: if ticks > 0 { ticks as f64 } else { 100.0 },
+            page_size: if page_size > 0 {
+                page_size as u64
+            } else {
+                4096
+            },
+            previous: HashMap::new(),
+            previous_at: None,
+        }
+    }
+
+    // A process first seen in this interval counts from zero; on the very first sample
+    // there is no interval yet, so rates stay at zero.
+    fn usage(
+        &self,
+        pids: &[u32],
+        stats: &HashMap<u32, ProcStat>,
+        counters: &HashMap<u32, Counters>,
+        elapsed_secs: Option<f64>,
+    ) -> ResourceUsage {
+        let mut usage = ResourceUsage::default();
+        let (mut cpu_ticks, mut read_bytes, mut write_bytes) = (0u64, 0u64, 0u64);
+        for pid in pids {
+            let (Some(stat), Some(current)) = (stats.get(pid), counters.get(pid)) else {
+                continue;
+            };
+            usage.process_count += 1;
+            usage.rss_bytes += stat.rss_pages * self.page_size;
+
+            let before = self.previous.get(pid).copied().unwrap_or_default();
+            cpu_ticks += current.cpu_ticks.saturating_sub(before.cpu_ticks);
+            read_bytes += current.read_bytes.saturating_sub(before.read_bytes);
+            write_bytes += current.write_bytes.saturating_sub(before.write_bytes);
+        }
+
+        if let Some(elapsed) = elapsed_secs.filter(|elapsed| *elapsed > 0.0) {
+            usage.cpu_percent = cpu_ticks as f64 / self.ticks_per_sec / elapsed * 100.0;
+            usage.read_bytes_per_sec = (read_bytes as f64 / elapsed) as u64;
+            usage.write_bytes_per_sec = (write_bytes as f64 / elapsed) as u64;
+        }
+        usage
+    }
+
+    fn sample(&mut self, backend_pid: Option<u32>) -> ResourceSample {
+        let now = Instant::now();
+        let table = read_process_table();
+        let (shell, webview, backend) = classify(&table, std::process::id(), backend_pid);
+
+        let stats: HashMap<u32, ProcStat> = table.iter().map(|stat| (stat.pid, *stat)).collect();
+        let counters: HashMap<u32, Counters> = shell
+            .iter()
+            .chain(&webview)
+            .chain(&backend)
+            .filter_map(|pid| {
+                let stat = stats.get(pid)?;
+                let (read_bytes, write_bytes) = read_proc_io(*pid);
+                Some((
+                    *pid,
+                    Counters {
+                        cpu_ticks: stat.cpu_ticks,
+                        read_bytes,
+                        write_bytes,
+                    },
+                ))
+            })
+            .collect();
+
+        let elapsed_secs = self
+            .previous_at
+            .map(|previous_at| now.duration_since(previous_at).as_secs_f64());
+        let shell = self.usage(&shell, &stats, &counters, elapsed_secs);
+        let webview = self.usage(&webview, &stats, &counters, elapsed_secs);
+        let backend = self.usage(&backend, &stats, &counters, elapsed_secs);
+        let total = ResourceUsage {
+            process_count: shell.process_count + webview.process_count + backend.process_count,
+            cpu_percent: shell.cpu_percent + webview.cpu_percent + backend.cpu_percent,
+            rss_bytes: shell.rss_bytes + webview.rss_bytes + backend.rss_bytes,
+            read_bytes_per_sec: shell.read_bytes_per_sec
+                + webview.read_bytes_per_sec
+                + backend.read_bytes_per_sec,
+            write_bytes_per_sec: shell.write_bytes_per_sec
+                + webview.write_bytes_per_sec
+                + backend.write_bytes_per_sec,
+        };
+
+        self.previous = counters;
+        self.previous_at = Some(now);
+        ResourceSample {
+            timestamp_ms: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|elapsed| elapsed.as_millis() as u64)
+                .unwrap_or(0),
+            shell,
+            webview,
+            backend,
+            total,
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn monitor_resource_usage(app: AppHandle) {
+    let backend: State<BackendState> = app.state();
+    let usage_state: State<ResourceUsageState> = app.state();
+    let mut sampler = Sampler::new();
+
+    while !backend.shutting_down.load(Ordering::SeqCst) {
+        let backend_pid = backend
+            .child
+            .lock()
+            .ok()
+            .and_then(|guard| guard.as_ref().map(|child| child.id()));
+        let sample = sampler.sample(backend_pid);
+
+        if let Ok(mut history) = usage_state.history.lock() {
+            if history.len() == RESOURCE_HISTORY_LEN {
+                history.pop_front();
+            }
+            history.push_back(sample.clone());
+        }
+        let _ = app.emit_all(RESOURCE_USAGE_EVENT, sample);
+
+        thread::sleep(Duration::from_millis(RESOURCE_SAMPLE_INTERVAL_MS));
+    }
+}
+
+#[cfg(target_os = "linux")]
+pub fn start_resource_monitor(app: AppHandle) {
+    thread::spawn(move || monitor_resource_usage(app));
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn start_resource_monitor(_app: tauri::AppHandle) {}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+
+    fn stat(pid: u32, ppid: u32, pgrp: u32) -> ProcStat {
+        ProcStat {
+            pid,
+            ppid,
+            pgrp,
+            cpu_ticks: 0,
+            rss_pages: 0,
+        }
+    }
+
+    #[test]
+    fn parses_stat_with_awkward_comm() {
+        let raw = "4242 (Web Content (x)) S 100 4242 100 0 -1 4194560 500 0 0 0 \
+                   30 12 0 0 20 0 5 0 1234 100000000 2560 18446744073709551615";
+        assert_eq!(
+            parse_proc_stat(raw),
+            Some(ProcStat {
+                pid: 4242,
+                ppid: 100,
+                pgrp: 4242,
+                cpu_ticks: 42,
+                rss_pages: 2560,
+            })
+        );
+        assert_eq!(parse_proc_stat("garbage"), None);
+    }
+
+    #[test]
+    fn splits_tree_into_shell_webview_and_backend() {
+        let table = vec![
+            stat(1, 0, 1),
+            stat(100, 1, 100),
+            // WebKit helpers are plain children of the shell.
+            stat(110, 100, 100),
+            stat(111, 100, 100),
+            // The sidecar leads its own group.
+            stat(200, 100, 200),
+            stat(201, 200, 200),
+            // Reparented to init but still in the sidecar's group.
+            stat(202, 1, 200),
+            stat(300, 1, 300),
+        ];
+
+        let (shell, mut webview, mut backend) = classify(&table, 100, Some(200));
+        webview.sort_unstable();
+        backend.sort_unstable();
+        assert_eq!(shell, vec![100]);
+        assert_eq!(webview, vec![110, 111]);
+        assert_eq!(backend, vec![200, 201, 202]);
+    }
+}
@@ -0,0 +1,147 @@
+use std::fmt;
+use std::io;
+use std::process::Child;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::backend_socket::send_backend_request;
+
+const SHUTDOWN_DEADLINE_ENV: &str = "LOCUS_SHUTDOWN_DEADLINE_SECS";
+pub const DEFAULT_SHUTDOWN_DEADLINE_SECS: u64 = 10;
+// When the event loop is already exiting there is only time to flush the DB.
+pub const EXIT_SHUTDOWN_DEADLINE_SECS: u64 = 3;
+// Share of the deadline the backend keeps for flushing SQLite and its lifespan shutdown.
+const SHUTDOWN_EXIT_MARGIN_SECS: u64 = 2;
+const SHUTDOWN_REQUEST_TIMEOUT_MS: u64 = 2000;
+const SHUTDOWN_POLL_INTERVAL_MS: u64 = 100;
+
+pub enum ShutdownOutcome {
+    AlreadyExited,
+    Graceful(Duration),
+    DeadlineExceeded(Duration),
+    Refused(u16),
+    Unreachable(io::Error),
+}
+
+impl ShutdownOutcome {
+    pub fn needs_signals(&self) -> bool {
+        !matches!(self, Self::AlreadyExited | Self::Graceful(_))
+    }
+}
+
+impl fmt::Display for ShutdownOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::AlreadyExited => write!(f, "backend had already exited"),
+            Self::Graceful(elapsed) => write!(
+                f,
+                "backend drained and exited gracefully in {}ms",
+                elapsed.as_millis()
+            ),
+            Self::DeadlineExceeded(deadline) => write!(
+                f,
+                "backend did not exit within {}s of the shutdown request; falling back to signals",
+                deadline.as_secs()
+            ),
+            Self::Refused(status) => write!(
+                f,
+                "backend rejected the shutdown request (HTTP {}); falling back to signals",
+                status
+            ),
+            Self::Unreachable(err) => write!(
+                f,
+                "backend shutdown request failed ({}); falling back to signals",
+                err
+            ),
+        }
+    }
+}
+
+pub fn shutdown_deadline() -> Duration {
+    let secs = std::env::var(SHUTDOWN_DEADLINE_ENV)
+        .ok()
+        .and_then(|raw| raw.trim().parse::<u64>().ok())
+        .unwrap_or_else(|| crate::config::shell_config().backend.shutdown_deadline_secs);
+    Duration::from_secs(secs)
+}
+
+fn drain_timeout(deadline: Duration) -> Duration {
+    deadline.saturating_sub(Duration::from_secs(SHUTDOWN_EXIT_MARGIN_SECS))
+}
+
+fn wait_for_exit(child: &mut Child, deadline: Duration) -> Option<Duration> {
+    let started = Instant::now();
+    while started.elapsed() < deadline {
+        match child.try_wait() {
+            Ok(Some(_)) => return Some(started.elapsed()),
+            Ok(None) => {}
+            Err(_) => return None,
+        }
+        thread::sleep(Duration::from_millis(SHUTDOWN_POLL_INTERVAL_MS));
+    }
+    None
+}
+
+// Asks the backend to drain its backup queue and flush the DB before it exits, so an
+// in-flight backup or restore is not cut off. Signals are left to the caller.
+pub fn request_graceful_shutdown(
+    child: &mut Child,
+    port: u16,
+    deadline: Duration,
+) -> ShutdownOutcome {
+    if let Ok(Some(_)) = child.try_wait() {
+        return ShutdownOutcome::AlreadyExited;
+    }
+
+    let body = format!(
+        "{{\"drain_timeout_seconds\":{:.1}}}",
+        drain_timeout(deadline).as_secs_f64()
+    );
+    let headers = [("Content-Type".to_string(), "application/json".to_string())];
+    match send_backend_request(
+        port,
+        "POST",
+        "/system/shutdown",
+        &headers,
+        body.as_bytes(),
+        Duration::from_millis(SHUTDOWN_REQUEST_TIMEOUT_MS),
+    ) {
+        Ok(response) if response.status == 202 => {}
+        Ok(response) => return ShutdownOutcome::Refused(response.status),
+        Err(err) => return ShutdownOutcome::Unreachable(err),
+    }
+
+    match wait_for_exit(child, deadline) {
+        Some(elapsed) => ShutdownOutcome::Graceful(elapsed),
+        None => ShutdownOutcome::DeadlineExceeded(deadline),
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::process::Command;
+
+    #[test]
+    fn drain_leaves_margin_for_exit() {
+        assert_eq!(
+            drain_timeout(Duration::from_secs(10)),
+            Duration::from_secs(8)
+        );
+        assert_eq!(drain_timeout(Duration::from_secs(1)), Duration::ZERO);
+    }
+
+    #[test]
+    fn waits_for_exit_until_deadline() {
+        let mut quick = Command::new("true").spawn().expect("failed to spawn true");
+        assert!(wait_for_exit(&mut quick, Duration::from_secs(5)).is_some());
+
+        let mut slow = Command::new("sleep")
+            .arg("5")
+            .spawn()
+            .expect("failed to spawn sleep");
+        assert!(wait_for_exit(&mut slow, Duration::from_millis(200)).is_none());
+        let _ = slow.kill();
+        let _ = slow.wait();
+    }
+}
@@ -0,0 +1,131 @@
+use std::fmt::{self, Write as _};
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+use sha2::{Digest, Sha256};
+
+// Computed by build.rs from src-tauri/binaries; empty when the build had no sidecar.
+pub const BACKEND_SHA256: &str = env!("LOCUS_BACKEND_SHA256");
+pub const WINDOW_PROBE_SHA256: &str = env!("LOCUS_WINDOW_PROBE_SHA256");
+
+#[derive(Debug)]
+pub enum IntegrityError {
+    NoDigest {
+        path: PathBuf,
+    },
+    Unreadable {
+        path: PathBuf,
+        source: io::Error,
+    },
+    Mismatch {
+        path: PathBuf,
+        expected: String,
+        actual: String,
+    },
+}
+
+impl fmt::Display for IntegrityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoDigest { path } => write!(
+                f,
+                "no digest was embedded for '{}' when this app was built",
+                path.display()
+            ),
+            Self::Unreadable { path, source } => {
+                write!(
+                    f,
+                    "failed to read '{}' for verification: {}",
+                    path.display(),
+                    source
+                )
+            }
+            Self::Mismatch {
+                path,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "'{}' was modified after install (sha256 {}, expected {})",
+                path.display(),
+                actual,
+                expected
+            ),
+        }
+    }
+}
+
+impl std::error::Error for IntegrityError {}
+
+fn sha256_file(path: &Path) -> io::Result<String> {
+    let mut file = File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; 64 * 1024];
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(hasher
+        .finalize()
+        .iter()
+        .fold(String::with_capacity(64), |mut hex, byte| {
+            let _ = write!(hex, "{:02x}", byte);
+            hex
+        }))
+}
+
+// Checked right before every spawn. The file could still be swapped in between, but
+// doing so needs write access to the install dir, which already means game over.
+pub fn verify_sidecar(path: &Path, expected: &str) -> Result<(), IntegrityError> {
+    if expected.is_empty() {
+        // Debug builds run sidecars built locally, often after the shell was compiled.
+        if cfg!(debug_assertions) {
+            return Ok(());
+        }
+        return Err(IntegrityError::NoDigest {
+            path: path.to_path_buf(),
+        });
+    }
+
+    let actual = sha256_file(path).map_err(|source| IntegrityError::Unreadable {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    if !actual.eq_ignore_ascii_case(expected) {
+        return Err(IntegrityError::Mismatch {
+            path: path.to_path_buf(),
+            expected: expected.to_string(),
+            actual,
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::scratch_dir;
+
+    #[test]
+    fn rejects_modified_sidecar() {
+        let dir = scratch_dir("sidecar-integrity");
+        let path = dir.join("locus-backend");
+        std::fs::write(&path, b"abc").expect("failed to write sidecar");
+
+        // Well-known SHA-256 of "abc".
+        let expected = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+        assert!(verify_sidecar(&path, expected).is_ok());
+
+        std::fs::write(&path, b"abd").expect("failed to rewrite sidecar");
+        assert!(matches!(
+            verify_sidecar(&path, expected),
+            Err(IntegrityError::Mismatch { .. })
+        ));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
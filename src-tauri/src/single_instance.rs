@@ -0,0 +1,505 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+#[cfg(not(unix))]
+use std::net::{TcpListener, TcpStream};
+#[cfg(unix)]
+use std::os::unix::net::{UnixListener, UnixStream};
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tauri::{AppHandle, Manager};
+use tracing::{info, warn};
+
+use crate::backend_socket::request_backend_json;
+use crate::profiles::PROFILE_ARG;
+use crate::BackendState;
+
+pub const SECOND_INSTANCE_EVENT: &str = "locus://second-instance";
+
+const LOCK_FILE_NAME: &str = "locus.lock";
+#[cfg(unix)]
+const HANDOFF_SOCKET_DIR_NAME: &str = "run";
+#[cfg(unix)]
+const HANDOFF_SOCKET_FILE_NAME: &str = "instance.sock";
+#[cfg(not(unix))]
+const HANDOFF_PORT_FILE_NAME: &str = "instance.port";
+const HANDOFF_CONNECT_ATTEMPTS: u32 = 20;
+const HANDOFF_RETRY_DELAY_MS: u64 = 150;
+const HANDOFF_IO_TIMEOUT_MS: u64 = 2000;
+const HANDOFF_POLL_INTERVAL_MS: u64 = 100;
+const HANDOFF_ACK: &str = "ok";
+
+#[cfg(unix)]
+type HandoffListener = UnixListener;
+#[cfg(not(unix))]
+type HandoffListener = TcpListener;
+#[cfg(unix)]
+type HandoffStream = UnixStream;
+#[cfg(not(unix))]
+type HandoffStream = TcpStream;
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+pub struct LaunchRequest {
+    pub args: Vec<String>,
+    pub cwd: Option<PathBuf>,
+}
+
+impl LaunchRequest {
+    pub fn current() -> Self {
+        Self {
+            args: std::env::args().skip(1).collect(),
+            cwd: std::env::current_dir().ok(),
+        }
+    }
+
+    fn folders(&self) -> Vec<PathBuf> {
+        let mut folders = Vec::new();
+        let mut args = self.args.iter();
+        while let Some(arg) = args.next() {
+            if arg == PROFILE_ARG {
+                args.next();
+                continue;
+            }
+            if arg.starts_with('-') {
+                continue;
+            }
+            let path = match &self.cwd {
+                Some(cwd) => cwd.join(arg),
+                None => PathBuf::from(arg),
+            };
+            if path.is_dir() {
+                folders.push(path);
+            }
+        }
+        folders
+    }
+}
+
+struct HandoffWorker {
+    stop: Arc<AtomicBool>,
+    thread: JoinHandle<()>,
+}
+
+// Dropping it stops the handoff listener and then releases the lock, so a later launch
+// on this data dir starts on its own instead of being handed to us.
+pub struct InstanceGuard {
+    _lock: File,
+    data_dir: PathBuf,
+    listener: Option<HandoffListener>,
+    worker: Option<HandoffWorker>,
+}
+
+impl InstanceGuard {
+    pub fn start_handoff_listener(&mut self, app: AppHandle) {
+        self.serve_handoffs(move |stream| {
+            if let Err(err) = handle_handoff(&app, stream) {
+                warn!(error = %err, "ignoring malformed second-launch request");
+            }
+        });
+    }
+
+    fn serve_handoffs<F>(&mut self, mut handle: F)
+    where
+        F: FnMut(HandoffStream) + Send + 'static,
+    {
+        let Some(listener) = self.listener.take() else {
+            return;
+        };
+        if let Err(err) = listener.set_nonblocking(true) {
+            warn!(error = %err, "failed to start single-instance handoff");
+            return;
+        }
+        let stop = Arc::new(AtomicBool::new(false));
+        let stopped = Arc::clone(&stop);
+        let thread = thread::spawn(move || {
+            while !stopped.load(Ordering::SeqCst) {
+                let stream = match listener.accept() {
+                    Ok((stream, _)) => stream,
+                    Err(err) => {
+                        if err.kind() != io::ErrorKind::WouldBlock {
+                            warn!(error = %err, "single-instance handoff accept failed");
+                        }
+                        thread::sleep(Duration::from_millis(HANDOFF_POLL_INTERVAL_MS));
+                        continue;
+                    }
+                };
+                let timeout = Some(Duration::from_millis(HANDOFF_IO_TIMEOUT_MS));
+                let _ = stream.set_nonblocking(false);
+                let _ = stream.set_read_timeout(timeout);
+                let _ = stream.set_write_timeout(timeout);
+                handle(stream);
+            }
+        });
+        self.worker = Some(HandoffWorker { stop, thread });
+    }
+}
+
+impl Drop for InstanceGuard {
+    fn drop(&mut self) {
+        if let Some(worker) = self.worker.take() {
+            worker.stop.store(true, Ordering::SeqCst);
+            let _ = worker.thread.join();
+        }
+        remove_handoff_endpoint(&self.data_dir);
+    }
+}
+
+// Replacing the guard releases the previous data dir for other launches.
+pub struct ActiveInstance(Mutex<Option<InstanceGuard>>);
+
+impl ActiveInstance {
+    pub fn new(guard: Option<InstanceGuard>) -> Self {
+        Self(Mutex::new(guard))
+    }
+
+    pub fn replace(&self, guard: InstanceGuard) {
+        let previous = match self.0.lock() {
+            Ok(mut current) => current.replace(guard),
+            Err(_) => return,
+        };
+        // Joins the old handoff thread, so not while holding the lock.
+        drop(previous);
+    }
+
+    pub fn start_handoff_listener(&self, app: AppHandle) {
+        if let Ok(mut current) = self.0.lock() {
+            if let Some(guard) = current.as_mut() {
+                guard.start_handoff_listener(app);
+            }
+        }
+    }
+}
+
+pub enum InstanceRole {
+    Primary(InstanceGuard),
+    Secondary,
+}
+
+#[cfg(unix)]
+fn try_lock_exclusive(file: &File) -> io::Result<bool> {
+    use std::os::unix::io::AsRawFd;
+
+    // SAFETY: flock only reads the descriptor, which `file` keeps open.
+    if unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_EX | libc::LOCK_NB) } == 0 {
+        return Ok(true);
+    }
+    let err = io::Error::last_os_error();
+    if err.kind() == io::ErrorKind::WouldBlock {
+        Ok(false)
+    } else {
+        Err(err)
+    }
+}
+
+#[cfg(unix)]
+fn open_lock_file(path: &Path) -> io::Result<Option<File>> {
+    let file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(path)?;
+    Ok(try_lock_exclusive(&file)?.then_some(file))
+}
+
+// Windows has no advisory locks in std; an exclusive share mode does the same job.
+#[cfg(not(unix))]
+fn open_lock_file(path: &Path) -> io::Result<Option<File>> {
+    use std::os::windows::fs::OpenOptionsExt;
+    const ERROR_SHARING_VIOLATION: i32 = 32;
+
+    match OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .share_mode(0)
+        .open(path)
+    {
+        Ok(file) => Ok(Some(file)),
+        Err(err) if err.raw_os_error() == Some(ERROR_SHARING_VIOLATION) => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
+#[cfg(unix)]
+fn handoff_socket_path(data_dir: &Path) -> PathBuf {
+    data_dir
+        .join(HANDOFF_SOCKET_DIR_NAME)
+        .join(HANDOFF_SOCKET_FILE_NAME)
+}
+
+// Only the lock holder gets here, so a leftover socket is always stale.
+#[cfg(unix)]
+fn bind_handoff_listener(data_dir: &Path) -> io::Result<HandoffListener> {
+    use std::os::unix::fs::{DirBuilderExt, PermissionsExt};
+
+    let socket_path = handoff_socket_path(data_dir);
+    if let Some(dir) = socket_path.parent() {
+        fs::DirBuilder::new()
+            .recursive(true)
+            .mode(0o700)
+            .create(dir)?;
+        fs::set_permissions(dir, fs::Permissions::from_mode(0o700))?;
+    }
+    match fs::remove_file(&socket_path) {
+        Err(err) if err.kind() != io::ErrorKind::NotFound => return Err(err),
+        _ => {}
+    }
+    UnixListener::bind(&socket_path)
+}
+
+// No Unix sockets in std on Windows: listen on loopback and publish the port next to
+// the lock, which itself cannot be read while the primary holds it unshared.
+#[cfg(not(unix))]
+fn bind_handoff_listener(data_dir: &Path) -> io::Result<HandoffListener> {
+    let listener = TcpListener::bind(("127.0.0.1", 0))?;
+    fs::write(
+        data_dir.join(HANDOFF_PORT_FILE_NAME),
+        listener.local_addr()?.port().to_string(),
+    )?;
+    Ok(listener)
+}
+
+#[cfg(unix)]
+fn remove_handoff_endpoint(data_dir: &Path) {
+    let _ = fs::remove_file(handoff_socket_path(data_dir));
+}
+
+#[cfg(not(unix))]
+fn remove_handoff_endpoint(data_dir: &Path) {
+    let _ = fs::remove_file(data_dir.join(HANDOFF_PORT_FILE_NAME));
+}
+
+#[cfg(unix)]
+fn connect_to_primary(data_dir: &Path) -> io::Result<UnixStream> {
+    UnixStream::connect(handoff_socket_path(data_dir))
+}
+
+#[cfg(not(unix))]
+fn connect_to_primary(data_dir: &Path) -> io::Result<TcpStream> {
+    let raw = fs::read_to_string(data_dir.join(HANDOFF_PORT_FILE_NAME))?;
+    let port = raw
+        .trim()
+        .parse::<u16>()
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+    TcpStream::connect(("127.0.0.1", port))
+}
+
+fn send_launch_request(data_dir: &Path, request: &LaunchRequest) -> io::Result<()> {
+    let mut last_error = io::Error::new(io::ErrorKind::NotFound, "primary instance not reachable");
+    // The primary may still be between taking the lock and binding its listener.
+    for _ in 0..HANDOFF_CONNECT_ATTEMPTS {
+        match connect_to_primary(data_dir) {
+            Ok(mut stream) => {
+                let timeout = Some(Duration::from_millis(HANDOFF_IO_TIMEOUT_MS));
+                stream.set_read_timeout(timeout)?;
+                stream.set_write_timeout(timeout)?;
+
+                let mut line = serde_json::to_string(request)?;
+                line.push('\n');
+                stream.write_all(line.as_bytes())?;
+
+                let mut ack = String::new();
+                BufReader::new(stream).read_line(&mut ack)?;
+                if ack.trim() == HANDOFF_ACK {
+                    return Ok(());
+                }
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "primary instance rejected the launch request",
+                ));
+            }
+            Err(err) => last_error = err,
+        }
+        thread::sleep(Duration::from_millis(HANDOFF_RETRY_DELAY_MS));
+    }
+    Err(last_error)
+}
+
+// Two shells on one data dir would run two backends against the same SQLite DB and
+// storage, so a second launch only hands its arguments over and steps aside.
+pub fn acquire(data_dir: &Path, request: &LaunchRequest) -> io::Result<InstanceRole> {
+    crate::data_dir_permissions::create_private_dir(data_dir)?;
+    let lock_path = data_dir.join(LOCK_FILE_NAME);
+
+    match open_lock_file(&lock_path)? {
+        Some(lock) => {
+            let listener = match bind_handoff_listener(data_dir) {
+                Ok(listener) => Some(listener),
+                Err(err) => {
+                    warn!(error = %err, "failed to open single-instance handoff");
+                    None
+                }
+            };
+            Ok(InstanceRole::Primary(InstanceGuard {
+                _lock: lock,
+                data_dir: data_dir.to_path_buf(),
+                listener,
+                worker: None,
+            }))
+        }
+        // An unreachable primary still owns the data dir.
+        None => {
+            if let Err(err) = send_launch_request(data_dir, request) {
+                warn!(error = %err, "could not reach the running Locus instance");
+            }
+            Ok(InstanceRole::Secondary)
+        }
+    }
+}
+
+// None means another launch already owns the data dir.
+pub fn claim_data_dir(app: &AppHandle, data_dir: &Path) -> io::Result<Option<InstanceGuard>> {
+    let request = LaunchRequest {
+        args: Vec::new(),
+        cwd: None,
+    };
+    match acquire(data_dir, &request)? {
+        InstanceRole::Primary(mut guard) => {
+            guard.start_handoff_listener(app.clone());
+            Ok(Some(guard))
+        }
+        InstanceRole::Secondary => Ok(None),
+    }
+}
+
+fn focus_existing_window(app: &AppHandle) {
+    let window = app
+        .get_window("main")
+        .or_else(|| app.get_window(crate::startup::SPLASH_WINDOW_LABEL));
+    if let Some(window) = window {
+        let _ = window.show();
+        let _ = window.unminimize();
+        let _ = window.set_focus();
+    }
+}
+
+fn watch_folders(app: &AppHandle, folders: &[PathBuf]) {
+    let port = app.state::<BackendState>().port.load(Ordering::SeqCst);
+    for folder in folders {
+        let body = json!({ "path": folder.to_string_lossy() });
+        match request_backend_json(port, "POST", "/files/watched", Some(body)) {
+            Ok(_) => info!(path = %folder.display(), "watching folder from second launch"),
+            Err(err) => warn!(path = %folder.display(), error = %err, "could not watch folder"),
+        }
+    }
+}
+
+fn handle_handoff(app: &AppHandle, stream: HandoffStream) -> io::Result<()> {
+    let mut reader = BufReader::new(stream);
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+    let request: LaunchRequest = serde_json::from_str(line.trim())?;
+
+    // Acknowledge first: adding a folder can take longer than the second launch waits.
+    let stream = reader.get_mut();
+    stream.write_all(HANDOFF_ACK.as_bytes())?;
+    stream.write_all(b"\n")?;
+    stream.flush()?;
+
+    info!(args = request.args.len(), "second launch handed over");
+    watch_folders(app, &request.folders());
+    focus_existing_window(app);
+    let _ = app.emit_all(SECOND_INSTANCE_EVENT, request);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::scratch_dir;
+
+    #[test]
+    fn second_acquire_forwards_request_to_primary() {
+        let dir = scratch_dir("single-instance");
+        let request = LaunchRequest {
+            args: vec!["/home/user/projects".to_string()],
+            cwd: Some(dir.clone()),
+        };
+
+        let mut guard = match acquire(&dir, &request).expect("first acquire failed") {
+            InstanceRole::Primary(guard) => guard,
+            InstanceRole::Secondary => panic!("first launch must become primary"),
+        };
+        let listener = guard.listener.take().expect("primary should listen");
+        let primary = thread::spawn(move || {
+            let (stream, _) = listener.accept().expect("accept failed");
+            let mut reader = BufReader::new(stream);
+            let mut line = String::new();
+            reader.read_line(&mut line).expect("read failed");
+            reader.get_mut().write_all(b"ok\n").expect("ack failed");
+            serde_json::from_str::<LaunchRequest>(line.trim()).expect("bad request")
+        });
+
+        assert!(matches!(
+            acquire(&dir, &request).expect("second acquire failed"),
+            InstanceRole::Secondary
+        ));
+        assert_eq!(primary.join().expect("primary thread panicked"), request);
+
+        drop(guard);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn dropping_the_guard_stops_answering_later_launches() {
+        let dir = scratch_dir("single-instance-drop");
+        let request = LaunchRequest {
+            args: Vec::new(),
+            cwd: None,
+        };
+
+        let mut guard = match acquire(&dir, &request).expect("first acquire failed") {
+            InstanceRole::Primary(guard) => guard,
+            InstanceRole::Secondary => panic!("first launch must become primary"),
+        };
+        guard.serve_handoffs(|stream| {
+            let mut reader = BufReader::new(stream);
+            let mut line = String::new();
+            let _ = reader.read_line(&mut line);
+            let _ = reader.get_mut().write_all(b"ok\n");
+        });
+        assert!(send_launch_request(&dir, &request).is_ok());
+
+        drop(guard);
+        assert!(connect_to_primary(&dir).is_err());
+        assert!(matches!(
+            acquire(&dir, &request).expect("third acquire failed"),
+            InstanceRole::Primary(_)
+        ));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn folder_args_resolve_against_the_launch_directory() {
+        let dir = scratch_dir("launch-folders");
+        fs::create_dir_all(dir.join("docs")).expect("failed to create folder");
+        fs::write(dir.join("notes.txt"), "x").expect("failed to create file");
+        let request = LaunchRequest {
+            args: [
+                "--profile",
+                "docs",
+                "docs",
+                "notes.txt",
+                "--verbose",
+                "missing",
+            ]
+            .iter()
+            .map(|arg| arg.to_string())
+            .collect(),
+            cwd: Some(dir.clone()),
+        };
+
+        assert_eq!(request.folders(), vec![dir.join("docs")]);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
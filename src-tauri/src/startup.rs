@@ -0,0 +1,220 @@
+use std::path::Path;
+use std::sync::atomic::Ordering;
+use std::sync::Mutex;
+use std::thread;
+
+use serde::Serialize;
+use tauri::{AppHandle, Manager, State, Window, WindowBuilder, WindowUrl};
+use tracing::{error, info, warn};
+
+use crate::startup_dialog::{prompt_data_dir_repair, prompt_startup_recovery, StartupRecovery};
+use crate::{
+    backend_logs, backend_socket, compat, config, create_main_window, data_dir_permissions,
+    discovery, install_backend_child, reserve_backend_port, resolve_locus_data_dir,
+    spawn_release_backend, supervisor, BackendStartupError, BackendState,
+};
+
+const STARTUP_PROGRESS_EVENT: &str = "locus://startup-progress";
+pub const SPLASH_WINDOW_LABEL: &str = "splash";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StartupPhase {
+    Spawning,
+    WaitingForHealth,
+    DbReady,
+    WatcherReady,
+    Ready,
+    Failed,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct StartupProgress {
+    phase: StartupPhase,
+    port: u16,
+    message: Option<String>,
+}
+
+pub struct StartupState {
+    progress: Mutex<StartupProgress>,
+}
+
+impl Default for StartupState {
+    fn default() -> Self {
+        Self {
+            progress: Mutex::new(StartupProgress {
+                phase: StartupPhase::Spawning,
+                port: config::shell_config().backend.port,
+                message: None,
+            }),
+        }
+    }
+}
+
+fn report_progress(app: &AppHandle, phase: StartupPhase, port: u16, message: Option<String>) {
+    let progress = StartupProgress {
+        phase,
+        port,
+        message,
+    };
+
+    let state: State<StartupState> = app.state();
+    if let Ok(mut guard) = state.progress.lock() {
+        *guard = progress.clone();
+    }
+    let _ = app.emit_all(STARTUP_PROGRESS_EVENT, progress);
+}
+
+// The splash asks for the current phase on load in case it missed earlier events.
+#[tauri::command]
+pub fn get_startup_progress(state: State<StartupState>) -> Option<StartupProgress> {
+    state.progress.lock().ok().map(|guard| guard.clone())
+}
+
+pub fn create_splash_window(app: &AppHandle) -> tauri::Result<Window> {
+    WindowBuilder::new(
+        app,
+        SPLASH_WINDOW_LABEL,
+        WindowUrl::App("splash.html".into()),
+    )
+    .title("Locus")
+    .inner_size(420.0, 260.0)
+    .resizable(false)
+    .decorations(false)
+    .center()
+    .build()
+}
+
+fn hand_off_to_main_window(app: &AppHandle) {
+    if app.get_window("main").is_none() {
+        if let Err(err) = create_main_window(app) {
+            error!(error = %err, "failed to create main window");
+            return;
+        }
+    }
+
+    if let Some(splash) = app.get_window(SPLASH_WINDOW_LABEL) {
+        let _ = splash.close();
+    }
+}
+
+// True when the user asked to retry; otherwise the app is already exiting.
+fn recover_from_startup_failure(
+    app: &AppHandle,
+    err: &BackendStartupError,
+    port: u16,
+    log_dir: &Path,
+) -> bool {
+    error!(port, error = %err, "backend startup failed");
+    report_progress(app, StartupPhase::Failed, port, Some(err.to_string()));
+
+    let splash = app.get_window(SPLASH_WINDOW_LABEL);
+    match prompt_startup_recovery(splash.as_ref(), err, port, log_dir) {
+        StartupRecovery::Retry => true,
+        StartupRecovery::Quit => {
+            app.exit(1);
+            false
+        }
+    }
+}
+
+// Checked before any backend reads the data. False means the app is already exiting.
+fn ensure_private_data_dir(app: &AppHandle, data_dir: &Path) -> bool {
+    loop {
+        let problem = match data_dir_permissions::inspect(data_dir) {
+            None => return true,
+            Some(problem) if !problem.is_fatal() => {
+                warn!(dir = %data_dir.display(), "{}", problem);
+                return true;
+            }
+            Some(problem) => problem,
+        };
+        error!(dir = %data_dir.display(), "{}", problem);
+
+        let splash = app.get_window(SPLASH_WINDOW_LABEL);
+        if !prompt_data_dir_repair(splash.as_ref(), &problem, data_dir) {
+            app.exit(1);
+            return false;
+        }
+        // Loops back to inspect, so a failed repair asks again instead of starting anyway.
+        if let Err(err) = data_dir_permissions::repair(data_dir) {
+            error!(dir = %data_dir.display(), error = %err, "failed to repair data dir");
+        }
+    }
+}
+
+fn run_release_startup(app: AppHandle) {
+    let data_dir = resolve_locus_data_dir();
+    let log_dir = backend_logs::backend_log_dir(&data_dir);
+    let backend: State<BackendState> = app.state();
+
+    if !ensure_private_data_dir(&app, &data_dir) {
+        return;
+    }
+
+    let socket_mode = backend_socket::unix_socket_path().is_some();
+
+    loop {
+        // Attach without owning the lifecycle: the child stays None, so neither the
+        // supervisor nor shutdown will touch a backend someone else runs. Socket-mode
+        // backends are private to one launch and never advertise themselves.
+        let existing = if socket_mode {
+            None
+        } else {
+            discovery::find_running_backend(&data_dir)
+        };
+        if let Some(existing) = existing {
+            info!(
+                pid = existing.pid,
+                port = existing.port,
+                "attaching to running backend: {}",
+                compat::describe_combination(existing.backend_version.as_ref())
+            );
+            // Someone else owns this backend, so the only way out is to stop or upgrade it.
+            if let Err(reason) = compat::check_backend_version(existing.backend_version.as_ref()) {
+                let err = BackendStartupError::Incompatible(reason);
+                if recover_from_startup_failure(&app, &err, existing.port, &log_dir) {
+                    continue;
+                }
+                return;
+            }
+            backend.port.store(existing.port, Ordering::SeqCst);
+            report_progress(&app, StartupPhase::Ready, existing.port, None);
+            hand_off_to_main_window(&app);
+            return;
+        }
+
+        let (port, listener) = if socket_mode {
+            (0, None)
+        } else {
+            let settings = config::shell_config();
+            reserve_backend_port(settings.backend.port, settings.backend.port_search_limit)
+        };
+        backend.port.store(port, Ordering::SeqCst);
+
+        let on_phase = |phase: StartupPhase| report_progress(&app, phase, port, None);
+        match spawn_release_backend(port, listener, &on_phase) {
+            Ok((child, port)) => {
+                if !install_backend_child(&backend, child) {
+                    return;
+                }
+                backend.port.store(port, Ordering::SeqCst);
+                report_progress(&app, StartupPhase::Ready, port, None);
+                supervisor::start_backend_supervisor(app.clone());
+                backend_socket::start_file_event_bridge(app.clone());
+                hand_off_to_main_window(&app);
+                return;
+            }
+            Err(err) => {
+                if recover_from_startup_failure(&app, &err, port, &log_dir) {
+                    continue;
+                }
+                return;
+            }
+        }
+    }
+}
+
+pub fn start_release_startup(app: AppHandle) {
+    thread::spawn(move || run_release_startup(app));
+}
@@ -0,0 +1,294 @@
+use std::io;
+use std::path::Path;
+use std::process::Command;
+use std::thread;
+
+use tauri::api::dialog::blocking::MessageDialogBuilder;
+use tauri::api::dialog::{MessageDialogButtons, MessageDialogKind};
+use tauri::utils::assets::EmbeddedAssets;
+use tauri::{AppHandle, Context, Window};
+use tracing::{info, warn};
+
+use crate::backend_logs::tail_backend_log;
+use crate::backend_socket;
+use crate::config::{self, ConfigError};
+use crate::data_dir_permissions::{self, PermissionProblem};
+use crate::BackendStartupError;
+
+const STARTUP_DIALOG_LOG_LINES: usize = 12;
+
+pub enum StartupRecovery {
+    Retry,
+    Quit,
+}
+
+fn describe_startup_failure(
+    error: &BackendStartupError,
+    port: u16,
+    log_dir: &Path,
+    log_lines: &[String],
+) -> String {
+    let endpoint = match backend_socket::unix_socket_path() {
+        Some(socket_path) => format!("Socket: {}", socket_path.display()),
+        None => format!("Port tried: {}", port),
+    };
+    let mut description = format!(
+        "Locus could not start its local backend.\n\nReason: {}\n{}\nLogs: {}",
+        error,
+        endpoint,
+        log_dir.display()
+    );
+
+    if matches!(error, BackendStartupError::Incompatible(_)) {
+        description.push_str(
+            "\n\nThe app and its backend come from different Locus releases. \
+             Reinstall Locus, or stop a separately running backend service, so both match.",
+        );
+    }
+
+    if matches!(error, BackendStartupError::SidecarIntegrity(_)) {
+        description.push_str(
+            "\n\nA bundled Locus program no longer matches the one this release shipped with. \
+             Reinstall Locus from a trusted download before retrying.",
+        );
+    }
+
+    if log_lines.is_empty() {
+        description.push_str("\n\nThe backend did not write any log output.");
+    } else {
+        description.push_str("\n\nLast backend log lines:\n");
+        description.push_str(&log_lines.join("\n"));
+    }
+
+    description
+}
+
+fn open_in_file_manager(path: &Path) -> io::Result<()> {
+    std::fs::create_dir_all(path)?;
+    open_path(path)
+}
+
+// Folders open in the file manager, files in their default application.
+pub fn open_path(path: &Path) -> io::Result<()> {
+    #[cfg(target_os = "windows")]
+    let opener = "explorer";
+    #[cfg(target_os = "macos")]
+    let opener = "open";
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    let opener = "xdg-open";
+
+    // explorer.exe reports a non-zero exit code even on success, so only spawn errors count.
+    Command::new(opener).arg(path).status().map(|_| ())
+}
+
+// Applies to every blocking dialog in the shell: it waits on the running event loop,
+// so it must be shown from a thread other than the main one.
+fn ask(
+    parent: Option<&Window>,
+    kind: MessageDialogKind,
+    title: &str,
+    message: &str,
+    ok: &str,
+    cancel: &str,
+) -> bool {
+    let mut dialog = MessageDialogBuilder::new(title, message)
+        .kind(kind)
+        .buttons(MessageDialogButtons::OkCancelWithLabels(
+            String::from(ok),
+            String::from(cancel),
+        ));
+    if let Some(parent) = parent {
+        dialog = dialog.parent(parent);
+    }
+    dialog.show()
+}
+
+// Native message dialogs only offer two buttons, so "Open logs" lives on the quit
+// confirmation and loops back to the failure dialog once the folder is open.
+pub fn prompt_startup_recovery(
+    parent: Option<&Window>,
+    error: &BackendStartupError,
+    port: u16,
+    log_dir: &Path,
+) -> StartupRecovery {
+    let log_lines = tail_backend_log(log_dir, STARTUP_DIALOG_LOG_LINES);
+    let description = describe_startup_failure(error, port, log_dir, &log_lines);
+
+    loop {
+        let retry = ask(
+            parent,
+            MessageDialogKind::Error,
+            "Locus failed to start",
+            &description,
+            "Retry",
+            "Quit",
+        );
+        if retry {
+            return StartupRecovery::Retry;
+        }
+
+        let open_logs = ask(
+            parent,
+            MessageDialogKind::Info,
+            "Quit Locus",
+            &format!(
+                "Open the backend logs before quitting?\n\n{}",
+                log_dir.display()
+            ),
+            "Open logs",
+            "Quit",
+        );
+        if !open_logs {
+            return StartupRecovery::Quit;
+        }
+
+        if let Err(err) = open_in_file_manager(log_dir) {
+            warn!(
+                dir = %log_dir.display(),
+                error = %err,
+                "failed to open log folder"
+            );
+        }
+    }
+}
+
+// True when the user agreed to restrict the folder; quitting is the only alternative.
+pub fn prompt_data_dir_repair(
+    parent: Option<&Window>,
+    problem: &PermissionProblem,
+    data_dir: &Path,
+) -> bool {
+    let mut message = format!(
+        "Locus keeps your file history and snapshots in\n{}\n\nbut {}.",
+        data_dir.display(),
+        problem
+    );
+    if problem.is_repairable() {
+        message.push_str("\n\nRepair limits the folder to your user account (mode 700).");
+        if let Some(target) = data_dir_permissions::link_target(data_dir) {
+            message.push_str(&format!(
+                " The data directory is a link, so this changes the folder it points to:\n{}",
+                target.display()
+            ));
+        }
+        return ask(
+            parent,
+            MessageDialogKind::Warning,
+            "Locus data is not private",
+            &message,
+            "Repair",
+            "Quit",
+        );
+    }
+
+    message.push_str(
+        "\n\nLocus will not use a folder another account owns. \
+         Change its owner, or point LOCUS_DATA_DIR at a folder of your own.",
+    );
+    let mut dialog = MessageDialogBuilder::new("Locus data is not private", message)
+        .kind(MessageDialogKind::Error)
+        .buttons(MessageDialogButtons::OkWithLabel(String::from("Quit")));
+    if let Some(parent) = parent {
+        dialog = dialog.parent(parent);
+    }
+    dialog.show();
+    false
+}
+
+fn prompt_config_fix(app: AppHandle, mut error: ConfigError) {
+    let path = config::config_path();
+    loop {
+        let fix = ask(
+            None,
+            MessageDialogKind::Error,
+            "Locus settings are invalid",
+            &format!("{}\n\nLocus will not start until the file is fixed.", error),
+            "Fix",
+            "Quit",
+        );
+        if !fix {
+            app.exit(1);
+            return;
+        }
+
+        if let Err(err) = open_path(&path) {
+            warn!(path = %path.display(), error = %err, "failed to open settings file");
+        }
+        let reload = ask(
+            None,
+            MessageDialogKind::Info,
+            "Locus settings are invalid",
+            &format!(
+                "Save your changes to\n{}\nthen start Locus.",
+                path.display()
+            ),
+            "Start",
+            "Quit",
+        );
+        if !reload {
+            app.exit(1);
+            return;
+        }
+
+        match config::init() {
+            Ok(()) => {
+                info!(path = %path.display(), "settings fixed; restarting");
+                app.restart();
+                return;
+            }
+            Err(err) => error = err,
+        }
+    }
+}
+
+// Runs instead of the app when locus.toml is invalid at launch. Nothing has resolved the
+// data dir or taken the instance lock yet, so a restart starts cleanly on the fixed file.
+pub fn run_config_recovery(context: Context<EmbeddedAssets>, error: ConfigError) {
+    tauri::Builder::default()
+        .setup(move |app| {
+            let handle = app.handle();
+            thread::spawn(move || prompt_config_fix(handle, error));
+            Ok(())
+        })
+        .build(context)
+        .expect("error while building tauri application")
+        .run(|_, _| {});
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use std::time::Duration;
+
+    #[test]
+    fn failure_description_includes_reason_port_and_log_tail() {
+        let error = BackendStartupError::ReadyTimeout {
+            port: 8004,
+            timeout: Duration::from_secs(45),
+        };
+        let log_dir = PathBuf::from("/tmp/locus/logs");
+        let lines = vec![
+            String::from("INFO: booting"),
+            String::from("ERROR: db locked"),
+        ];
+
+        let description = describe_startup_failure(&error, 8004, &log_dir, &lines);
+
+        assert!(description.contains("within 45s"));
+        assert!(description.contains("Port tried: 8004"));
+        assert!(description.contains("/tmp/locus/logs"));
+        assert!(description.ends_with("INFO: booting\nERROR: db locked"));
+    }
+
+    #[test]
+    fn failure_description_notes_missing_log_output() {
+        let error = BackendStartupError::BinaryNotFound {
+            path: PathBuf::from("/opt/locus/locus-backend"),
+        };
+        let description = describe_startup_failure(&error, 8000, &PathBuf::from("/tmp/logs"), &[]);
+
+        assert!(description.contains("/opt/locus/locus-backend"));
+        assert!(description.contains("did not write any log output"));
+    }
+}
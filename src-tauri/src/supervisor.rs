@@ -0,0 +1,296 @@
+use std::collections::VecDeque;
+use std::net::TcpListener;
+use std::sync::atomic::Ordering;
+use std::sync::PoisonError;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use tauri::{AppHandle, Manager, State};
+use tracing::{error, warn};
+
+use crate::{backend_socket, install_backend_child, spawn_release_backend, BackendState};
+
+const BACKEND_STATUS_EVENT: &str = "locus://backend-status";
+
+const SUPERVISOR_POLL_INTERVAL_MS: u64 = 1000;
+const RESTART_BACKOFF_BASE_MS: u64 = 500;
+const RESTART_BACKOFF_MAX_MS: u64 = 30_000;
+const CRASH_LOOP_MAX_CRASHES: usize = 5;
+const CRASH_LOOP_WINDOW_SECS: u64 = 300;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum BackendStatusKind {
+    Running,
+    Reconnecting,
+    Failed,
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct BackendStatusPayload {
+    state: BackendStatusKind,
+    port: u16,
+    attempt: u32,
+    message: Option<String>,
+}
+
+struct CrashWindow {
+    window: Duration,
+    max_crashes: usize,
+    crashes: VecDeque<Instant>,
+}
+
+impl CrashWindow {
+    fn new(window: Duration, max_crashes: usize) -> Self {
+        Self {
+            window,
+            max_crashes,
+            crashes: VecDeque::new(),
+        }
+    }
+
+    fn record(&mut self, now: Instant) -> usize {
+        while let Some(oldest) = self.crashes.front() {
+            if now.duration_since(*oldest) > self.window {
+                self.crashes.pop_front();
+            } else {
+                break;
+            }
+        }
+        self.crashes.push_back(now);
+        self.crashes.len()
+    }
+
+    fn exhausted(&self) -> bool {
+        self.crashes.len() > self.max_crashes
+    }
+}
+
+fn restart_delay(attempt: u32) -> Duration {
+    let exponent = attempt.saturating_sub(1).min(16);
+    let delay_ms = RESTART_BACKOFF_BASE_MS.saturating_mul(1_u64 << exponent);
+    Duration::from_millis(delay_ms.min(RESTART_BACKOFF_MAX_MS))
+}
+
+fn emit_backend_status(
+    app: &AppHandle,
+    state: BackendStatusKind,
+    port: u16,
+    attempt: u32,
+    message: Option<String>,
+) {
+    let payload = BackendStatusPayload {
+        state,
+        port,
+        attempt,
+        message,
+    };
+    let _ = app.emit_all(BACKEND_STATUS_EVENT, payload);
+}
+
+enum ChildProbe {
+    Alive,
+    Exited(String),
+    Detached,
+}
+
+fn probe_backend_child(state: &BackendState) -> ChildProbe {
+    let mut guard = state.child.lock().unwrap_or_else(PoisonError::into_inner);
+    let child = match guard.as_mut() {
+        Some(child) => child,
+        None => return ChildProbe::Detached,
+    };
+
+    match child.try_wait() {
+        Ok(Some(status)) => {
+            // Helpers outlive a crashed leader; clear them before a restart spawns more.
+            crate::process_group::terminate(child);
+            guard.take();
+            ChildProbe::Exited(format!("backend exited with status {}", status))
+        }
+        Ok(None) => ChildProbe::Alive,
+        Err(err) => {
+            crate::process_group::terminate(child);
+            let _ = child.wait();
+            guard.take();
+            ChildProbe::Exited(format!(
+                "failed while polling backend process status: {}",
+                err
+            ))
+        }
+    }
+}
+
+// The supervisor only starts once it owns a child, so a missing one is a failed restart.
+fn restart_reason(state: &BackendState) -> Option<String> {
+    if state.restarting.load(Ordering::SeqCst) {
+        return None;
+    }
+    match probe_backend_child(state) {
+        ChildProbe::Alive => None,
+        ChildProbe::Exited(reason) => Some(reason),
+        ChildProbe::Detached => Some(String::from("backend is not running")),
+    }
+}
+
+fn child_installed(state: &BackendState) -> bool {
+    state
+        .child
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .is_some()
+}
+
+fn supervise_backend(app: AppHandle) {
+    let state: State<BackendState> = app.state();
+    let mut port = state.port.load(Ordering::SeqCst);
+    let mut crash_window = CrashWindow::new(
+        Duration::from_secs(CRASH_LOOP_WINDOW_SECS),
+        CRASH_LOOP_MAX_CRASHES,
+    );
+
+    loop {
+        thread::sleep(Duration::from_millis(SUPERVISOR_POLL_INTERVAL_MS));
+        if state.shutting_down.load(Ordering::SeqCst) {
+            return;
+        }
+        let Some(mut last_error) = restart_reason(&state) else {
+            continue;
+        };
+        if state.shutting_down.load(Ordering::SeqCst) {
+            return;
+        }
+        warn!(port, "{}", last_error);
+
+        loop {
+            let attempt = crash_window.record(Instant::now()) as u32;
+            if crash_window.exhausted() {
+                error!(
+                    crashes = attempt,
+                    window_secs = CRASH_LOOP_WINDOW_SECS,
+                    "backend keeps crashing; giving up"
+                );
+                emit_backend_status(
+                    &app,
+                    BackendStatusKind::Failed,
+                    port,
+                    attempt,
+                    Some(last_error),
+                );
+                return;
+            }
+
+            emit_backend_status(
+                &app,
+                BackendStatusKind::Reconnecting,
+                port,
+                attempt,
+                Some(last_error.clone()),
+            );
+            thread::sleep(restart_delay(attempt));
+            if state.shutting_down.load(Ordering::SeqCst) {
+                return;
+            }
+            // The switch starts its own backend once the new data dir is in place.
+            if state.restarting.load(Ordering::SeqCst) || child_installed(&state) {
+                break;
+            }
+
+            let listener = match backend_socket::unix_socket_path() {
+                Some(_) => None,
+                None => TcpListener::bind(("127.0.0.1", port)).ok(),
+            };
+            match spawn_release_backend(port, listener, &|_| {}) {
+                Ok((child, restarted_port)) => {
+                    if !install_backend_child(&state, child) {
+                        return;
+                    }
+                    port = restarted_port;
+                    state.port.store(port, Ordering::SeqCst);
+                    emit_backend_status(&app, BackendStatusKind::Running, port, attempt, None);
+                    break;
+                }
+                Err(err) => {
+                    warn!(attempt, error = %err, "backend restart attempt failed");
+                    last_error = err.to_string();
+                }
+            }
+        }
+    }
+}
+
+pub fn start_backend_supervisor(app: AppHandle) {
+    thread::spawn(move || supervise_backend(app));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn restart_delay_grows_exponentially_and_caps() {
+        assert_eq!(
+            restart_delay(1),
+            Duration::from_millis(RESTART_BACKOFF_BASE_MS)
+        );
+        assert_eq!(
+            restart_delay(2),
+            Duration::from_millis(RESTART_BACKOFF_BASE_MS * 2)
+        );
+        assert_eq!(
+            restart_delay(3),
+            Duration::from_millis(RESTART_BACKOFF_BASE_MS * 4)
+        );
+        assert_eq!(
+            restart_delay(40),
+            Duration::from_millis(RESTART_BACKOFF_MAX_MS)
+        );
+    }
+
+    #[test]
+    fn crash_window_forgets_crashes_outside_the_window() {
+        let mut window = CrashWindow::new(Duration::from_secs(10), 2);
+        let start = Instant::now();
+
+        assert_eq!(window.record(start), 1);
+        assert_eq!(window.record(start + Duration::from_secs(1)), 2);
+        assert!(!window.exhausted());
+        assert_eq!(window.record(start + Duration::from_secs(2)), 3);
+        assert!(window.exhausted());
+
+        assert_eq!(window.record(start + Duration::from_secs(30)), 1);
+        assert!(!window.exhausted());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn a_failed_restart_is_retried_instead_of_ending_supervision() {
+        use std::process::Command;
+        use std::sync::atomic::{AtomicBool, AtomicU16};
+        use std::sync::Mutex;
+
+        let mut command = Command::new("sleep");
+        command.arg("30");
+        crate::process_group::isolate(&mut command);
+        let state = BackendState {
+            child: Mutex::new(Some(command.spawn().expect("failed to spawn sleep"))),
+            port: AtomicU16::new(0),
+            shutting_down: AtomicBool::new(false),
+            restarting: AtomicBool::new(true),
+        };
+        assert_eq!(restart_reason(&state), None);
+
+        crate::stop_backend_child(&state);
+        // No locus-backend sits next to the test binary, so the restart cannot spawn.
+        assert!(crate::restart_backend_child(&state).is_err());
+        assert!(matches!(probe_backend_child(&state), ChildProbe::Detached));
+        assert_eq!(restart_reason(&state), None);
+
+        state.restarting.store(false, Ordering::SeqCst);
+        assert_eq!(
+            restart_reason(&state).as_deref(),
+            Some("backend is not running")
+        );
+    }
+}
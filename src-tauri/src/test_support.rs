@@ -0,0 +1,16 @@
+use std::fs;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Unique per call, so tests running in parallel never share a directory; each test
+// removes its own when done.
+pub fn scratch_dir(label: &str) -> PathBuf {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_nanos())
+        .unwrap_or(0);
+    let dir =
+        std::env::temp_dir().join(format!("locus-{}-{}-{}", label, std::process::id(), nanos));
+    fs::create_dir_all(&dir).expect("failed to create scratch dir");
+    dir
+}
@@ -0,0 +1,252 @@
+use std::sync::atomic::Ordering;
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+
+use serde::Serialize;
+use serde_json::{json, Value};
+use tauri::api::dialog::{MessageDialogBuilder, MessageDialogKind};
+use tauri::{AppHandle, CustomMenuItem, Manager, State, SystemTrayMenu};
+use tracing::{info, warn};
+
+use crate::backend_socket::request_backend_json;
+use crate::{resolve_locus_data_dir, startup_dialog, BackendState};
+
+pub const TRAY_CONTROLS_EVENT: &str = "locus://tray-controls";
+
+const TRAY_MONITORING_ITEM_ID: &str = "file_monitoring";
+const TRAY_SNAPSHOTS_ITEM_ID: &str = "activity_snapshots";
+const TRAY_LOCK_ITEM_ID: &str = "lock_now";
+const TRAY_DATA_FOLDER_ITEM_ID: &str = "open_data_folder";
+const TRAY_REFRESH_INTERVAL_MS: u64 = 5000;
+
+// None means the backend did not say: it is unreachable, or locked and refusing the API.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct TrayControls {
+    locked: Option<bool>,
+    monitoring_paused: Option<bool>,
+    snapshots_enabled: Option<bool>,
+}
+
+#[derive(Default)]
+pub struct TrayControlsState {
+    current: Mutex<TrayControls>,
+}
+
+struct ItemState {
+    id: &'static str,
+    selected: bool,
+    enabled: bool,
+}
+
+pub fn is_tray_item(id: &str) -> bool {
+    matches!(
+        id,
+        TRAY_MONITORING_ITEM_ID
+            | TRAY_SNAPSHOTS_ITEM_ID
+            | TRAY_LOCK_ITEM_ID
+            | TRAY_DATA_FOLDER_ITEM_ID
+    )
+}
+
+// Everything starts disabled; the first refresh enables what the backend reports on.
+pub fn add_tray_items(menu: SystemTrayMenu) -> SystemTrayMenu {
+    menu.add_item(
+        CustomMenuItem::new(TRAY_MONITORING_ITEM_ID.to_string(), "File monitoring").disabled(),
+    )
+    .add_item(
+        CustomMenuItem::new(TRAY_SNAPSHOTS_ITEM_ID.to_string(), "Activity snapshots").disabled(),
+    )
+    .add_item(CustomMenuItem::new(TRAY_LOCK_ITEM_ID.to_string(), "Lock now").disabled())
+    .add_item(CustomMenuItem::new(
+        TRAY_DATA_FOLDER_ITEM_ID.to_string(),
+        "Open data folder",
+    ))
+}
+
+fn item_states(controls: &TrayControls) -> [ItemState; 3] {
+    [
+        ItemState {
+            id: TRAY_MONITORING_ITEM_ID,
+            selected: controls.monitoring_paused == Some(false),
+            enabled: controls.monitoring_paused.is_some(),
+        },
+        ItemState {
+            id: TRAY_SNAPSHOTS_ITEM_ID,
+            selected: controls.snapshots_enabled == Some(true),
+            enabled: controls.snapshots_enabled.is_some(),
+        },
+        // Unlocking needs the passphrase, so the tray can only ever lock.
+        ItemState {
+            id: TRAY_LOCK_ITEM_ID,
+            selected: controls.locked == Some(true),
+            enabled: controls.locked == Some(false),
+        },
+    ]
+}
+
+fn parse_controls(
+    auth: Option<&Value>,
+    monitoring: Option<&Value>,
+    snapshots: Option<&Value>,
+) -> TrayControls {
+    let flag = |body: Option<&Value>, name: &str| body.and_then(|body| body.get(name)?.as_bool());
+    TrayControls {
+        locked: flag(auth, "locked"),
+        monitoring_paused: flag(monitoring, "paused"),
+        snapshots_enabled: flag(snapshots, "enabled"),
+    }
+}
+
+fn fetch_controls(port: u16) -> TrayControls {
+    let auth = request_backend_json(port, "GET", "/auth/status", None).ok();
+    let unlocked = auth.as_ref().and_then(|body| body.get("locked")?.as_bool()) == Some(false);
+    // A locked backend answers everything else with 401, so do not bother asking.
+    let (monitoring, snapshots) = if unlocked {
+        (
+            request_backend_json(port, "GET", "/monitoring", None).ok(),
+            request_backend_json(port, "GET", "/settings/snapshots", None).ok(),
+        )
+    } else {
+        (None, None)
+    };
+    parse_controls(auth.as_ref(), monitoring.as_ref(), snapshots.as_ref())
+}
+
+fn apply_to_tray(app: &AppHandle, controls: &TrayControls) {
+    let tray = app.tray_handle();
+    for item in item_states(controls) {
+        let handle = tray.get_item(item.id);
+        let _ = handle.set_selected(item.selected);
+        let _ = handle.set_enabled(item.enabled);
+    }
+}
+
+// A rebuilt tray menu starts from the disabled defaults again.
+pub fn restore_tray_items(app: &AppHandle) {
+    let state: State<TrayControlsState> = app.state();
+    let current = state.current.lock().map(|current| *current);
+    if let Ok(current) = current {
+        apply_to_tray(app, &current);
+    }
+}
+
+fn refresh_tray_controls(app: &AppHandle) -> TrayControls {
+    let backend: State<BackendState> = app.state();
+    let latest = fetch_controls(backend.port.load(Ordering::SeqCst));
+    let state: State<TrayControlsState> = app.state();
+    let changed = match state.current.lock() {
+        Ok(mut current) if *current != latest => {
+            *current = latest;
+            true
+        }
+        _ => false,
+    };
+
+    if changed {
+        apply_to_tray(app, &latest);
+        let _ = app.emit_all(TRAY_CONTROLS_EVENT, latest);
+    }
+    latest
+}
+
+fn run_tray_item(app: &AppHandle, id: &str) -> Result<(), String> {
+    if id == TRAY_DATA_FOLDER_ITEM_ID {
+        let data_dir = resolve_locus_data_dir();
+        return startup_dialog::open_path(&data_dir)
+            .map_err(|err| format!("could not open '{}': {}", data_dir.display(), err));
+    }
+
+    // Act on what the backend reports now, not on a checkmark that may be stale.
+    let port = app.state::<BackendState>().port.load(Ordering::SeqCst);
+    let controls = fetch_controls(port);
+    let unavailable = || String::from("the backend is unreachable or locked");
+    match id {
+        TRAY_MONITORING_ITEM_ID => {
+            let paused = controls.monitoring_paused.ok_or_else(unavailable)?;
+            let target = if paused {
+                "/monitoring/resume"
+            } else {
+                "/monitoring/pause"
+            };
+            request_backend_json(port, "POST", target, None)?;
+            info!(paused = !paused, "file monitoring toggled from the tray");
+        }
+        TRAY_SNAPSHOTS_ITEM_ID => {
+            let enabled = controls.snapshots_enabled.ok_or_else(unavailable)?;
+            request_backend_json(
+                port,
+                "POST",
+                "/settings/snapshots",
+                Some(json!({ "enabled": !enabled })),
+            )?;
+            info!(
+                enabled = !enabled,
+                "activity snapshots toggled from the tray"
+            );
+        }
+        TRAY_LOCK_ITEM_ID => {
+            request_backend_json(port, "POST", "/auth/lock", None)?;
+            info!("locked from the tray");
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+// Runs off the tray callback: every item but the folder one is a backend round trip.
+pub fn handle_tray_item(app: &AppHandle, id: &str) {
+    if let Err(err) = run_tray_item(app, id) {
+        warn!(item = id, error = %err, "tray action failed");
+        MessageDialogBuilder::new("Locus", err)
+            .kind(MessageDialogKind::Error)
+            .show(|_| {});
+    }
+    // The menu toggles a clicked checkmark by itself, so resync even when nothing changed.
+    let latest = refresh_tray_controls(app);
+    apply_to_tray(app, &latest);
+}
+
+pub fn start_tray_controls_monitor(app: AppHandle) {
+    thread::spawn(move || loop {
+        let backend: State<BackendState> = app.state();
+        if backend.shutting_down.load(Ordering::SeqCst) {
+            return;
+        }
+        refresh_tray_controls(&app);
+        thread::sleep(Duration::from_millis(TRAY_REFRESH_INTERVAL_MS));
+    });
+}
+
+#[tauri::command]
+pub fn get_tray_controls(state: State<TrayControlsState>) -> TrayControls {
+    state
+        .current
+        .lock()
+        .map(|current| *current)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checkmarks_follow_backend_state() {
+        let controls = parse_controls(
+            Some(&json!({ "setup_required": false, "locked": false })),
+            Some(&json!({ "paused": true })),
+            Some(&json!({ "enabled": true, "interval_seconds": 10 })),
+        );
+        let items = item_states(&controls);
+        assert_eq!(items[0].id, TRAY_MONITORING_ITEM_ID);
+        assert!(!items[0].selected && items[0].enabled);
+        assert!(items[1].selected && items[1].enabled);
+        assert!(!items[2].selected && items[2].enabled);
+
+        let locked = parse_controls(Some(&json!({ "locked": true })), None, None);
+        assert!(item_states(&locked).iter().all(|item| !item.enabled));
+        assert!(item_states(&locked)[2].selected);
+        assert_eq!(parse_controls(None, None, None), TrayControls::default());
+    }
+}
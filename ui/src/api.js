@@ -37,6 +37,114 @@ export const BASE_URL = {
   }
 };
 
+const API_TOKEN_HEADER = 'X-Locus-Token';
+const API_TOKEN_QUERY_PARAM = 'locus_token';
+
+// Per-launch secret injected by the Tauri shell; absent in plain web dev runs.
+function readApiToken() {
+  if (typeof window === 'undefined') {
+    return '';
+  }
+  return String(window.__LOCUS_API_TOKEN || '').trim();
+}
+
+async function resolveApiToken() {
+  if (!isTauriRuntime()) {
+    return readApiToken();
+  }
+
+  // on_page_load injection can land after the first requests are already in flight.
+  for (let attempt = 0; attempt < 20; attempt += 1) {
+    const token = readApiToken();
+    if (token) {
+      return token;
+    }
+    await sleep(100);
+  }
+  return '';
+}
+
+// Linux socket transport: the backend has no TCP port and the shell relays requests over IPC.
+const SOCKET_PROXY_BASE_URL = 'locus-socket://backend';
+const FILE_EVENT_BRIDGE = 'locus://file-event';
+
+function isSocketProxyUrl(url) {
+  return String(url).startsWith(SOCKET_PROXY_BASE_URL);
+}
+
+function decodeBase64(text) {
+  const binary = atob(text || '');
+  const bytes = new Uint8Array(binary.length);
+  for (let index = 0; index < binary.length; index += 1) {
+    bytes[index] = binary.charCodeAt(index);
+  }
+  return bytes;
+}
+
+async function proxyFetch(url, options = {}) {
+  const { invoke } = await import('@tauri-apps/api/tauri');
+  const request = invoke('proxy_backend_request', {
+    method: options.method || 'GET',
+    path: String(url).slice(SOCKET_PROXY_BASE_URL.length) || '/',
+    headers: Object.entries(options.headers || {}),
+    body: typeof options.body === 'string' ? options.body : null
+  });
+
+  // invoke() cannot be cancelled; honour fetchWithTimeout's abort signal by racing it.
+  const aborted = new Promise((_resolve, reject) => {
+    options.signal?.addEventListener('abort', () => {
+      const error = new Error('Request aborted');
+      error.name = 'AbortError';
+      reject(error);
+    });
+  });
+
+  let proxied;
+  try {
+    proxied = await Promise.race([request, aborted]);
+  } catch (error) {
+    if (error?.name === 'AbortError') {
+      throw error;
+    }
+    // Surface proxy failures like fetch network errors so retry/startup hints still apply.
+    throw new TypeError(String(error || 'Failed to fetch'));
+  }
+
+  const noBody = proxied.status === 204 || proxied.status === 304;
+  return new Response(noBody ? null : decodeBase64(proxied.body), {
+    status: proxied.status,
+    headers: proxied.headers
+  });
+}
+
+export async function apiFetch(url, options = {}) {
+  if (isSocketProxyUrl(url)) {
+    return proxyFetch(url, options);
+  }
+
+  const token = await resolveApiToken();
+  if (!token) {
+    return fetch(url, options);
+  }
+  return fetch(url, {
+    ...options,
+    headers: {
+      ...(options.headers || {}),
+      [API_TOKEN_HEADER]: token
+    }
+  });
+}
+
+// EventSource cannot send custom headers, so the token rides in the query string.
+function withApiTokenQuery(url) {
+  const token = readApiToken();
+  if (!token) {
+    return url;
+  }
+  const separator = url.includes('?') ? '&' : '?';
+  return `${url}${separator}${API_TOKEN_QUERY_PARAM}=${encodeURIComponent(token)}`;
+}
+
 function sleep(ms) {
   return new Promise((resolve) => setTimeout(resolve, ms));
 }
@@ -57,7 +165,7 @@ async function fetchWithRetry(url, options = {}, { attempts = 1, retryDelayMs =
   let lastError = null;
   for (let attempt = 1; attempt <= attempts; attempt += 1) {
     try {
-      return await fetch(url, options);
+      return await apiFetch(url, options);
     } catch (error) {
       lastError = error;
       if (!isTransientNetworkError(error) || attempt >= attempts) {
@@ -131,7 +239,7 @@ async function resolveAuthBaseUrl() {
 
 export async function checkHealth() {
   const requestHealth = async (baseUrl) => {
-    const res = await fetch(`${baseUrl}/health`);
+    const res = await apiFetch(`${baseUrl}/health`);
     if (!res.ok) throw new Error('Network response was not ok');
     return await res.json();
   };
@@ -169,18 +277,18 @@ export async function checkHealth() {
 }
 
 export async function getWatchedPaths() {
-  const res = await fetch(`${BASE_URL}/files/watched`);
+  const res = await apiFetch(`${BASE_URL}/files/watched`);
   return await res.json();
 }
 
 export async function getWatchedTree() {
-  const res = await fetch(`${BASE_URL}/files/watched/tree`);
+  const res = await apiFetch(`${BASE_URL}/files/watched/tree`);
   if (!res.ok) throw new Error('Failed to fetch watched tree');
   return await res.json();
 }
 
 export async function addWatchedPath(path) {
-  const res = await fetch(`${BASE_URL}/files/watched`, {
+  const res = await apiFetch(`${BASE_URL}/files/watched`, {
     method: 'POST',
     headers: { 'Content-Type': 'application/json' },
     body: JSON.stringify({ path })
@@ -193,7 +301,7 @@ export async function addWatchedPath(path) {
 }
 
 export async function removeWatchedPath(pathId) {
-  const res = await fetch(`${BASE_URL}/files/watched/${pathId}`, {
+  const res = await apiFetch(`${BASE_URL}/files/watched/${pathId}`, {
     method: 'DELETE'
   });
   if (!res.ok) {
@@ -210,7 +318,7 @@ export async function relinkWatchedPath(oldPath, newPath, moveFiles = false) {
     move_files: !!moveFiles
   };
   
-  const res = await fetch(`${BASE_URL}/files/watched/relink`, {
+  const res = await apiFetch(`${BASE_URL}/files/watched/relink`, {
     method: 'POST',
     headers: { 'Content-Type': 'application/json' },
     body: JSON.stringify(payload)
@@ -223,7 +331,7 @@ export async function relinkWatchedPath(oldPath, newPath, moveFiles = false) {
 }
 
 export async function createCheckpointSession(payload = {}) {
-  const res = await fetch(`${BASE_URL}/checkpoints/sessions`, {
+  const res = await apiFetch(`${BASE_URL}/checkpoints/sessions`, {
     method: 'POST',
     headers: { 'Content-Type': 'application/json' },
     body: JSON.stringify(payload || {})
@@ -242,7 +350,7 @@ export async function listCheckpointSessions({ watchedPath = null, limit = 100 }
     url.searchParams.append('watched_path', watchedPath);
   }
 
-  const res = await fetch(url);
+  const res = await apiFetch(url);
   if (!res.ok) {
     const errorData = await res.json().catch(() => ({}));
     throw new Error(errorData.detail || 'Failed to list checkpoint sessions');
@@ -251,7 +359,7 @@ export async function listCheckpointSessions({ watchedPath = null, limit = 100 }
 }
 
 export async function getCheckpointSessionDetail(sessionId) {
-  const res = await fetch(`${BASE_URL}/checkpoints/sessions/${sessionId}`);
+  const res = await apiFetch(`${BASE_URL}/checkpoints/sessions/${sessionId}`);
   if (!res.ok) {
     const errorData = await res.json().catch(() => ({}));
     throw new Error(errorData.detail || 'Failed to fetch checkpoint session detail');
@@ -260,7 +368,7 @@ export async function getCheckpointSessionDetail(sessionId) {
 }
 
 export async function renameCheckpointSession(sessionId, name) {
-  const res = await fetch(`${BASE_URL}/checkpoints/sessions/${sessionId}`, {
+  const res = await apiFetch(`${BASE_URL}/checkpoints/sessions/${sessionId}`, {
     method: 'PATCH',
     headers: { 'Content-Type': 'application/json' },
     body: JSON.stringify({ name })
@@ -273,7 +381,7 @@ export async function renameCheckpointSession(sessionId, name) {
 }
 
 export async function diffCheckpointSessions(fromSessionId, toSessionId, includeUnchanged = false) {
-  const res = await fetch(`${BASE_URL}/checkpoints/sessions/diff`, {
+  const res = await apiFetch(`${BASE_URL}/checkpoints/sessions/diff`, {
     method: 'POST',
     headers: { 'Content-Type': 'application/json' },
     body: JSON.stringify({
@@ -290,7 +398,7 @@ export async function diffCheckpointSessions(fromSessionId, toSessionId, include
 }
 
 export async function restoreCheckpointSession(sessionId, payload = {}) {
-  const res = await fetch(`${BASE_URL}/checkpoints/sessions/${sessionId}/restore`, {
+  const res = await apiFetch(`${BASE_URL}/checkpoints/sessions/${sessionId}/restore`, {
     method: 'POST',
     headers: { 'Content-Type': 'application/json' },
     body: JSON.stringify(payload || {})
@@ -303,7 +411,7 @@ export async function restoreCheckpointSession(sessionId, payload = {}) {
 }
 
 export async function getActivityTimeline(limit = 50) {
-  const res = await fetch(`${BASE_URL}/activity/timeline?limit=${limit}`);
+  const res = await apiFetch(`${BASE_URL}/activity/timeline?limit=${limit}`);
   return await res.json();
 }
 
@@ -313,12 +421,45 @@ export async function getRecentFileEvents(limit = 50, path = null) {
   if (path) {
     url.searchParams.append('path', path);
   }
-  const res = await fetch(url);
+  const res = await apiFetch(url);
   return await res.json();
 }
 
+// Socket transport: the shell relays the SSE stream as Tauri events.
+function subscribeBridgedFileEvents(onEvent) {
+  let unlisten = null;
+  let closed = false;
+
+  import('@tauri-apps/api/event')
+    .then(({ listen }) => listen(FILE_EVENT_BRIDGE, (event) => onEvent(event.payload)))
+    .then((stop) => {
+      if (closed) {
+        stop();
+      } else {
+        unlisten = stop;
+      }
+    })
+    .catch((err) => {
+      console.error('File event bridge error:', err);
+    });
+
+  return {
+    close() {
+      closed = true;
+      if (unlisten) {
+        unlisten();
+        unlisten = null;
+      }
+    }
+  };
+}
+
 export function subscribeFileEvents(onEvent) {
-  const source = new EventSource(`${BASE_URL}/files/events/stream`);
+  if (isSocketProxyUrl(BASE_URL)) {
+    return subscribeBridgedFileEvents(onEvent);
+  }
+
+  const source = new EventSource(withApiTokenQuery(`${BASE_URL}/files/events/stream`));
   source.onmessage = (event) => {
     try {
       const data = JSON.parse(event.data);
@@ -336,7 +477,7 @@ export function subscribeFileEvents(onEvent) {
 export async function getFileVersions(path) {
   const url = new URL(`${BASE_URL}/files/versions`);
   url.searchParams.append('path', path);
-  const res = await fetch(url);
+  const res = await apiFetch(url);
   if (!res.ok) throw new Error('Failed to fetch versions');
   return await res.json();
 }
@@ -344,7 +485,7 @@ export async function getFileVersions(path) {
 export async function getCurrentFileVersion(path) {
   const url = new URL(`${BASE_URL}/files/current-version`);
   url.searchParams.append('path', path);
-  const res = await fetch(url);
+  const res = await apiFetch(url);
   if (!res.ok) throw new Error('Failed to fetch current version');
   return await res.json();
 }
@@ -352,7 +493,7 @@ export async function getCurrentFileVersion(path) {
 export async function getCurrentFileContent(path) {
   const url = new URL(`${BASE_URL}/files/current-content`);
   url.searchParams.append('path', path);
-  const res = await fetch(url);
+  const res = await apiFetch(url);
   if (!res.ok) {
     const errorData = await res.json().catch(() => ({}));
     throw new Error(errorData.detail || 'Failed to fetch current file content');
@@ -361,7 +502,7 @@ export async function getCurrentFileContent(path) {
 }
 
 export async function getFileVersionContent(versionId) {
-  const res = await fetch(`${BASE_URL}/files/versions/${versionId}/content`);
+  const res = await apiFetch(`${BASE_URL}/files/versions/${versionId}/content`);
   if (!res.ok) {
     const errorData = await res.json().catch(() => ({}));
     throw new Error(errorData.detail || 'Failed to fetch version content');
@@ -370,7 +511,7 @@ export async function getFileVersionContent(versionId) {
 }
 
 export async function restoreFileVersion(versionId) {
-  const res = await fetch(`${BASE_URL}/files/restore`, {
+  const res = await apiFetch(`${BASE_URL}/files/restore`, {
     method: 'POST',
     headers: { 'Content-Type': 'application/json' },
     body: JSON.stringify({ version_id: versionId })
@@ -380,7 +521,7 @@ export async function restoreFileVersion(versionId) {
 }
 
 export async function getSecuritySettings() {
-  const res = await fetch(`${BASE_URL}/settings/security`);
+  const res = await apiFetch(`${BASE_URL}/settings/security`);
   if (!res.ok) throw new Error('Failed to fetch security settings');
   return await res.json();
 }
@@ -538,25 +679,25 @@ export async function unlockAuth(passphrase) {
 }
 
 export async function lockAuth() {
-  const res = await fetch(`${BASE_URL}/auth/lock`, { method: 'POST' });
+  const res = await apiFetch(`${BASE_URL}/auth/lock`, { method: 'POST' });
   if (!res.ok) throw new Error('Failed to lock app');
   return await res.json();
 }
 
 export async function getDashboardSummary() {
-  const res = await fetch(`${BASE_URL}/dashboard/summary`);
+  const res = await apiFetch(`${BASE_URL}/dashboard/summary`);
   if (!res.ok) throw new Error('Failed to fetch dashboard summary');
   return await res.json();
 }
 
 export async function resetAuth() {
-  const res = await fetch(`${BASE_URL}/auth/reset`, { method: 'POST' });
+  const res = await apiFetch(`${BASE_URL}/auth/reset`, { method: 'POST' });
   if (!res.ok) throw new Error('Failed to reset app data');
   return await res.json();
 }
 
 export async function getSnapshotHistory(payload = {}) {
-  const res = await fetch(`${BASE_URL}/snapshots/history`, {
+  const res = await apiFetch(`${BASE_URL}/snapshots/history`, {
     method: 'POST',
     headers: { 'Content-Type': 'application/json' },
     body: JSON.stringify(payload || {})
@@ -569,7 +710,7 @@ export async function getSnapshotHistory(payload = {}) {
 }
 
 export async function executeSnapshotAction(actionType, value) {
-  const res = await fetch(`${BASE_URL}/snapshots/execute-action`, {
+  const res = await apiFetch(`${BASE_URL}/snapshots/execute-action`, {
     method: 'POST',
     headers: { 'Content-Type': 'application/json' },
     body: JSON.stringify({ action_type: actionType, value })
@@ -582,7 +723,7 @@ export async function executeSnapshotAction(actionType, value) {
 }
 
 export async function deleteSnapshot(snapshotId) {
-  const res = await fetch(`${BASE_URL}/snapshots/${snapshotId}`, {
+  const res = await apiFetch(`${BASE_URL}/snapshots/${snapshotId}`, {
     method: 'DELETE'
   });
   if (!res.ok) {
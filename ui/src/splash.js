@@ -0,0 +1,41 @@
+// splash.js - Startup progress reported by the shell while the backend warms up
+import './styles/splash.css';
+import { invoke } from '@tauri-apps/api/tauri';
+import { listen } from '@tauri-apps/api/event';
+
+const PHASES = ['spawning', 'waiting_for_health', 'db_ready', 'watcher_ready', 'ready'];
+const PHASE_LABELS = {
+  spawning: 'Starting backend...',
+  waiting_for_health: 'Waiting for backend...',
+  db_ready: 'Database ready',
+  watcher_ready: 'File watcher ready',
+  ready: 'Opening Locus...',
+  failed: 'Backend failed to start'
+};
+
+const phaseEl = document.getElementById('splash-phase');
+const barEl = document.getElementById('splash-bar');
+const detailEl = document.getElementById('splash-detail');
+let lastIndex = -1;
+
+function render(progress) {
+  if (!progress || !progress.phase) return;
+
+  const failed = progress.phase === 'failed';
+  const index = PHASES.indexOf(progress.phase);
+  // Events can race the initial query; never move the bar backwards.
+  if (!failed && index < lastIndex) return;
+  if (!failed) lastIndex = index;
+
+  phaseEl.textContent = PHASE_LABELS[progress.phase] || progress.phase;
+  barEl.style.width = `${Math.max(1, lastIndex + 1) / PHASES.length * 100}%`;
+  barEl.classList.toggle('splash-bar-failed', failed);
+  detailEl.textContent = failed ? String(progress.message || '') : `Port ${progress.port}`;
+}
+
+listen('locus://startup-progress', (event) => render(event.payload));
+invoke('get_startup_progress')
+  .then(render)
+  .catch(() => {
+    // Progress events will still arrive if the initial query fails.
+  });
@@ -462,3 +462,44 @@ test('setupAuth retries transient desktop network failures before succeeding', a
     }
   }
 });
+
+test('API requests carry the per-launch token injected by the shell', async () => {
+  const originalWindow = globalThis.window;
+  const originalFetch = globalThis.fetch;
+
+  globalThis.window = {
+    ...createWindowMock({
+      globalUrl: 'http://127.0.0.1:8033',
+      storageUrl: ''
+    }),
+    __LOCUS_API_TOKEN: 'launch-secret'
+  };
+
+  let seenToken = '';
+  globalThis.fetch = async (_url, options = {}) => {
+    seenToken = String(options.headers?.['X-Locus-Token'] || '');
+    return {
+      ok: true,
+      async json() {
+        return [];
+      }
+    };
+  };
+
+  try {
+    await getWatchedPaths();
+    assert.equal(seenToken, 'launch-secret');
+  } finally {
+    if (originalWindow === undefined) {
+      delete globalThis.window;
+    } else {
+      globalThis.window = originalWindow;
+    }
+
+    if (originalFetch === undefined) {
+      delete globalThis.fetch;
+    } else {
+      globalThis.fetch = originalFetch;
+    }
+  }
+});
@@ -1,3 +1,4 @@
+import { fileURLToPath } from 'node:url'
 import { defineConfig } from 'vite'
 import { svelte } from '@sveltejs/vite-plugin-svelte'
 
@@ -5,5 +6,13 @@ export default defineConfig({
   plugins: [svelte()],
   server: {
     port: 5173
+  },
+  build: {
+    rollupOptions: {
+      input: {
+        main: fileURLToPath(new URL('./index.html', import.meta.url)),
+        splash: fileURLToPath(new URL('./splash.html', import.meta.url))
+      }
+    }
   }
 })